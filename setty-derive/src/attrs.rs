@@ -41,9 +41,42 @@ impl syn::parse::Parse for Combine {
 
 /////////////////////////////////////////////////////////////////////////////////////////
 
+#[derive(Debug)]
+pub(crate) enum Reload {
+    Hot,
+    Restart,
+}
+
+impl Reload {
+    pub fn to_str_lit(&self) -> syn::LitStr {
+        let s = match self {
+            Reload::Hot => "hot",
+            Reload::Restart => "restart",
+        };
+        syn::LitStr::new(s, Span::call_site())
+    }
+}
+
+impl syn::parse::Parse for Reload {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let lit: syn::LitStr = input.parse()?;
+        match lit.value().as_str() {
+            "hot" => Ok(Self::Hot),
+            "restart" => Ok(Self::Restart),
+            _ => Err(syn::Error::new(
+                lit.span(),
+                r#"reload accepts: `"hot"` `"restart"`"#,
+            )),
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
 pub(crate) struct ConfigFieldOpts {
     pub default: Option<Option<syn::Expr>>,
     pub combine: Option<Combine>,
+    pub reload: Option<Reload>,
     pub validate: Option<proc_macro2::TokenStream>,
     pub span: Span,
 }
@@ -53,6 +86,7 @@ impl ConfigFieldOpts {
         Self {
             default: None,
             combine: None,
+            reload: None,
             validate: None,
             span,
         }
@@ -61,6 +95,7 @@ impl ConfigFieldOpts {
     pub fn merge(&mut self, other: Self) -> syn::Result<()> {
         self.default = other.default;
         self.combine = other.combine;
+        self.reload = other.reload;
         self.validate = other.validate;
         self.span = other.span;
         Ok(())
@@ -122,6 +157,9 @@ impl ConfigFieldOpts {
                 syn::parenthesized!(content in meta.input);
                 let combine: Combine = content.parse()?;
                 opts.combine = Some(combine);
+            } else if meta.path.is_ident("reload") {
+                let reload: Reload = meta.value()?.parse()?;
+                opts.reload = Some(reload);
             } else if meta.path.is_ident("validate") {
                 // Capture validate(...) token stream for later processing
                 let content;
@@ -116,6 +116,14 @@ pub(crate) fn config_impl(mut input: syn::DeriveInput) -> syn::Result<TokenStrea
                     });
                 }
 
+                #[cfg(feature = "derive-jsonschema")]
+                if let Some(reload) = opts.reload {
+                    let reload = reload.to_str_lit();
+                    field.attrs.push(syn::parse_quote! {
+                        #[schemars(extend("reload" = #reload))]
+                    });
+                }
+
                 if let Some(default) = opts.default {
                     let new_default_attr: syn::Attribute = if let Some(default_expr) = default {
                         let fname =
@@ -4,8 +4,9 @@ use crate::Value;
 
 /////////////////////////////////////////////////////////////////////////////////////////
 
-pub type OnDeprecatedClb =
-    dyn Fn(&[&str], /* reason */ Option<&str>, /* since */ Option<&str>) + 'static;
+/// Callback that can borrow, e.g. to filter reported fields
+pub type OnDeprecatedClb<'a> =
+    dyn Fn(&[&str], /* reason */ Option<&str>, /* since */ Option<&str>) + 'a;
 
 /////////////////////////////////////////////////////////////////////////////////////////
 
@@ -35,7 +36,7 @@ pub fn default_deprecation_clb(path: &[&str], reason: Option<&str>, since: Optio
 /////////////////////////////////////////////////////////////////////////////////////////
 
 /// Helper that scans a value for deprecated fields using the schema.
-pub fn check_deprecated_fields(schema: &Value, value: &Value, clb: &OnDeprecatedClb) {
+pub fn check_deprecated_fields(schema: &Value, value: &Value, clb: &OnDeprecatedClb<'_>) {
     let defs = &schema["$defs"];
     let mut path = Vec::new();
     check_deprecated_fields_rec(&mut path, schema, value, defs, clb)
@@ -49,19 +50,20 @@ fn check_deprecated_fields_rec<'a>(
     sch: &'a Value,
     value: &'a Value,
     defs: &'a Value,
-    clb: &OnDeprecatedClb,
+    clb: &OnDeprecatedClb<'_>,
 ) {
     if let Some(r) = sch.get("$ref").and_then(|v| v.as_str()) {
-        let (_, tname) = r.rsplit_once('/').unwrap();
-        let rsch = &defs[tname];
+        let Some(rsch) = r.rsplit_once('/').and_then(|(_, tname)| defs.get(tname)) else {
+            return;
+        };
         return check_deprecated_fields_rec(path, rsch, value, defs, clb);
     }
     if let Some(any_of) = sch.get("anyOf").and_then(|v| v.as_array()) {
         // `anyOf` only appears on nullable types
-        assert_eq!(any_of.len(), 2);
-        assert_eq!(any_of[1]["type"].as_str(), Some("null"));
-        let rsch = &any_of[0];
-        return check_deprecated_fields_rec(path, rsch, value, defs, clb);
+        for rsch in any_of {
+            check_deprecated_fields_rec(path, rsch, value, defs, clb);
+        }
+        return;
     }
     if let Some(one_of) = sch.get("oneOf").and_then(|v| v.as_array()) {
         // TODO: A perfectionist should consider the tag here
@@ -79,12 +81,12 @@ fn check_deprecated_fields_rec<'a>(
     };
 
     for (pname, pvalue) in value {
-        path.push(pname.as_str());
-
         let Some(pschema) = properties.get(pname) else {
             continue;
         };
 
+        path.push(pname.as_str());
+
         if pschema.get("deprecated") == Some(&Value::Bool(true)) {
             let reason = pschema["deprecation"]["reason"].as_str();
             let since = pschema["deprecation"]["since"].as_str();
@@ -0,0 +1,369 @@
+#![cfg(feature = "derive-jsonschema")]
+
+use crate::Value;
+use crate::errors::ReadError;
+#[cfg(feature = "case-enums-any")]
+use crate::locate::normalize;
+use crate::locate::{Segment, format_path, locate, sibling_path, suggest};
+use crate::merge_with_defaults::get_enum_tag_property_name;
+use crate::source::Layer;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Checks the merged value against the config schema, collecting all unknown fields,
+/// missing fields, type mismatches and values out of range, length or pattern instead of
+/// stopping at the first one.
+///
+/// Schema can be less strict than the deserialization (e.g. custom parsing of strings),
+/// so this is used to complement the deserialization error rather than replace it.
+pub(crate) fn check_schema(schema: &Value, value: &Value, layers: &[Layer]) -> Vec<ReadError> {
+    let mut checker = Checker {
+        defs: &schema["$defs"],
+        layers,
+        errors: Vec::new(),
+    };
+    checker.check(&mut Vec::new(), schema, value);
+    checker.errors
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+struct Checker<'a> {
+    defs: &'a Value,
+    layers: &'a [Layer],
+    errors: Vec<ReadError>,
+}
+
+impl<'a> Checker<'a> {
+    fn check(&mut self, path: &mut Vec<Segment<'a>>, sch: &'a Value, value: &'a Value) {
+        if let Some(r) = sch.get("$ref").and_then(Value::as_str) {
+            if let Some(rsch) = r.rsplit_once('/').and_then(|(_, name)| self.defs.get(name)) {
+                self.check(path, rsch, value);
+            }
+            return;
+        }
+        if let Some(variants) = sch
+            .get("anyOf")
+            .or_else(|| sch.get("oneOf"))
+            .and_then(Value::as_array)
+        {
+            return self.check_variants(path, variants, value);
+        }
+
+        if let Some(types) = sch.get("type")
+            && !type_matches(types, value)
+        {
+            let error = ReadError::InvalidType {
+                path: format_path(path),
+                expected: describe(sch),
+                found: unexpected(value),
+                location: self.locate(path),
+            };
+            self.errors.push(error);
+            return;
+        }
+
+        if let Some(allowed) = sch.get("enum").and_then(Value::as_array) {
+            return self.check_enum(path, allowed, value);
+        }
+
+        match value {
+            Value::Number(n) => self.check_range(path, sch, n),
+            Value::String(s) => self.check_string(path, sch, s),
+            Value::Array(items) => {
+                let Some(isch) = sch.get("items") else {
+                    return;
+                };
+                for (i, item) in items.iter().enumerate() {
+                    path.push(Segment::Index(i));
+                    self.check(path, isch, item);
+                    path.pop();
+                }
+            }
+            Value::Object(obj) => self.check_object(path, sch, obj),
+            _ => {}
+        }
+    }
+
+    fn check_object(
+        &mut self,
+        path: &mut Vec<Segment<'a>>,
+        sch: &'a Value,
+        obj: &'a serde_json::Map<String, Value>,
+    ) {
+        let properties = sch.get("properties").and_then(Value::as_object);
+
+        for (key, value) in obj {
+            path.push(Segment::Key(key));
+
+            if let Some(psch) = properties.and_then(|p| p.get(key)) {
+                self.check(path, psch, value);
+            } else {
+                match sch.get("additionalProperties") {
+                    Some(Value::Bool(false)) => {
+                        let expected: Vec<String> = properties
+                            .into_iter()
+                            .flatten()
+                            .map(|(k, _)| k.clone())
+                            .collect();
+                        let unknown = format_path(path);
+                        let suggestions = suggest(key, &expected)
+                            .into_iter()
+                            .map(|s| sibling_path(&unknown, &s))
+                            .collect();
+                        let error = ReadError::UnknownField {
+                            path: unknown,
+                            suggestions,
+                            expected,
+                            location: self.locate(path),
+                        };
+                        self.errors.push(error);
+                    }
+                    Some(asch @ Value::Object(_)) => self.check(path, asch, value),
+                    _ => {}
+                }
+            }
+
+            path.pop();
+        }
+
+        let required = sch.get("required").and_then(Value::as_array);
+        for name in required.into_iter().flatten().filter_map(Value::as_str) {
+            if obj.contains_key(name) {
+                continue;
+            }
+            path.push(Segment::Key(name));
+            let missing = format_path(path);
+            path.pop();
+
+            let error = ReadError::MissingField {
+                path: missing,
+                location: self.locate(path),
+            };
+            self.errors.push(error);
+        }
+    }
+
+    fn check_variants(
+        &mut self,
+        path: &mut Vec<Segment<'a>>,
+        variants: &'a [Value],
+        value: &'a Value,
+    ) {
+        // Internally tagged enums are resolved by the tag value
+        if let Value::Object(obj) = value
+            && let Some(tag) = get_enum_tag_property_name(variants)
+        {
+            let Some(tag_value) = obj.get(tag) else {
+                path.push(Segment::Key(tag.as_str()));
+                let missing = format_path(path);
+                path.pop();
+
+                let error = ReadError::MissingField {
+                    path: missing,
+                    location: self.locate(path),
+                };
+                self.errors.push(error);
+                return;
+            };
+
+            let expected: Vec<_> = variants
+                .iter()
+                .filter_map(|v| v["properties"][tag]["const"].as_str())
+                .collect();
+
+            let variant = variants
+                .iter()
+                .find(|v| is_variant(&v["properties"][tag]["const"], tag_value));
+
+            let Some(variant) = variant else {
+                let error = ReadError::UnknownVariant {
+                    path: format_path(path),
+                    variant: tag_value
+                        .as_str()
+                        .map_or_else(|| tag_value.to_string(), str::to_string),
+                    expected: expected.into_iter().map(str::to_string).collect(),
+                    location: self.locate(path),
+                };
+                self.errors.push(error);
+                return;
+            };
+
+            return self.check(path, variant, value);
+        }
+
+        // Otherwise report errors of the closest matching variant
+        let mut best: Option<Vec<ReadError>> = None;
+        for variant in variants {
+            let mut checker = Checker {
+                defs: self.defs,
+                layers: self.layers,
+                errors: Vec::new(),
+            };
+            checker.check(path, variant, value);
+
+            if checker.errors.is_empty() {
+                return;
+            }
+            if best.as_ref().is_none_or(|b| checker.errors.len() < b.len()) {
+                best = Some(checker.errors);
+            }
+        }
+        self.errors.extend(best.into_iter().flatten());
+    }
+
+    fn check_enum(&mut self, path: &[Segment<'a>], allowed: &'a [Value], value: &'a Value) {
+        if allowed.iter().any(|a| is_variant(a, value)) {
+            return;
+        }
+
+        let expected = allowed
+            .iter()
+            .map(|a| a.as_str().map_or_else(|| a.to_string(), str::to_string))
+            .collect();
+
+        let error = match value {
+            Value::String(variant) => ReadError::UnknownVariant {
+                path: format_path(path),
+                variant: variant.clone(),
+                expected,
+                location: self.locate(path),
+            },
+            _ => ReadError::InvalidType {
+                path: format_path(path),
+                expected: "variant identifier".to_string(),
+                found: unexpected(value),
+                location: self.locate(path),
+            },
+        };
+        self.errors.push(error);
+    }
+
+    fn check_range(&mut self, path: &[Segment<'a>], sch: &'a Value, n: &serde_json::Number) {
+        let Some(v) = n.as_f64() else {
+            return;
+        };
+        let below = sch["minimum"].as_f64().is_some_and(|min| v < min);
+        let above = sch["maximum"].as_f64().is_some_and(|max| v > max);
+
+        if below || above {
+            let error = ReadError::InvalidValue {
+                path: format_path(path),
+                expected: describe(sch),
+                found: unexpected(&Value::Number(n.clone())),
+                location: self.locate(path),
+            };
+            self.errors.push(error);
+        }
+    }
+
+    fn check_string(&mut self, path: &[Segment<'a>], sch: &'a Value, s: &str) {
+        let (min, max) = (sch["minLength"].as_u64(), sch["maxLength"].as_u64());
+        let len = s.chars().count() as u64;
+
+        let expected = match (min, max) {
+            (Some(min), Some(max)) if len < min || len > max => {
+                Some(format!("string of length between {min} and {max}"))
+            }
+            (Some(min), None) if len < min => Some(format!("string of length at least {min}")),
+            (None, Some(max)) if len > max => Some(format!("string of length at most {max}")),
+            _ => None,
+        };
+
+        // Patterns come from `regex` validation rules, so are only checked with them
+        #[cfg(feature = "derive-validate")]
+        let expected = expected.or_else(|| {
+            let pattern = sch["pattern"].as_str()?;
+            let re = regex::Regex::new(pattern).ok()?;
+            (!re.is_match(s)).then(|| format!("string matching `{pattern}`"))
+        });
+
+        if let Some(expected) = expected {
+            let error = ReadError::InvalidValue {
+                path: format_path(path),
+                expected,
+                found: unexpected(&Value::String(s.to_string())),
+                location: self.locate(path),
+            };
+            self.errors.push(error);
+        }
+    }
+
+    fn locate(&self, path: &[Segment<'_>]) -> Option<Box<crate::errors::Location>> {
+        locate(path, self.layers).map(Box::new)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Whether value names the enum variant, in any case with `case-enums-any` feature
+fn is_variant(name: &Value, value: &Value) -> bool {
+    if name == value {
+        return true;
+    }
+    #[cfg(feature = "case-enums-any")]
+    if let (Value::String(a), Value::String(b)) = (name, value) {
+        return normalize(a) == normalize(b);
+    }
+    false
+}
+
+fn type_matches(types: &Value, value: &Value) -> bool {
+    let matches = |t: &Value| match t.as_str() {
+        Some("null") => value.is_null(),
+        Some("boolean") => value.is_boolean(),
+        Some("string") => value.is_string(),
+        Some("integer") => value.is_i64() || value.is_u64(),
+        Some("number") => value.is_number(),
+        Some("array") => value.is_array(),
+        Some("object") => value.is_object(),
+        _ => true,
+    };
+    match types {
+        Value::Array(types) => types.iter().any(matches),
+        t => matches(t),
+    }
+}
+
+/// Describes expected type similarly to `serde`, e.g. `u16` or `string or null`
+fn describe(sch: &Value) -> String {
+    if let Some(format) = sch["format"].as_str() {
+        let rust = match format {
+            "double" => Some("f64".to_string()),
+            "float" => Some("f32".to_string()),
+            "uint" => Some("usize".to_string()),
+            "int" => Some("isize".to_string()),
+            f if f.starts_with("uint") => Some(format!("u{}", &f[4..])),
+            f if f.starts_with("int") => Some(format!("i{}", &f[3..])),
+            _ => None,
+        };
+        if let Some(rust) = rust {
+            return rust;
+        }
+    }
+
+    match &sch["type"] {
+        Value::Array(types) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" or "),
+        Value::String(t) => t.clone(),
+        _ => "value".to_string(),
+    }
+}
+
+/// Describes the value the same way as `serde::de::Unexpected`
+fn unexpected(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => format!("boolean `{b}`"),
+        Value::Number(n) if n.is_f64() => format!("floating point `{n}`"),
+        Value::Number(n) => format!("integer `{n}`"),
+        Value::String(s) => format!("string {s:?}"),
+        Value::Array(_) => "sequence".to_string(),
+        Value::Object(_) => "map".to_string(),
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
@@ -0,0 +1,196 @@
+#![cfg(feature = "derive-jsonschema")]
+
+use crate::Value;
+use crate::format::Conversion;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Converts values of formats that don't map onto config types directly (see
+/// [`Conversion`]) using the schema.
+///
+/// Values that can't be converted are left as is to be reported when deserializing.
+pub fn coerce(value: &mut Value, conversion: Conversion, sch: &Value, defs: &Value) {
+    if conversion == Conversion::None {
+        return;
+    }
+    if let Some(r) = sch.get("$ref").and_then(|v| v.as_str()) {
+        let Some(rsch) = r.rsplit_once('/').and_then(|(_, tname)| defs.get(tname)) else {
+            return;
+        };
+        return coerce(value, conversion, rsch, defs);
+    }
+    if let Some(any_of) = sch.get("anyOf").and_then(|v| v.as_array()) {
+        // `anyOf` only appears on nullable types
+        let Some(rsch) = any_of
+            .iter()
+            .find(|v| v.get("type").and_then(|t| t.as_str()) != Some("null"))
+        else {
+            return;
+        };
+        return coerce(value, conversion, rsch, defs);
+    }
+    if let Some(variants) = sch.get("oneOf").and_then(|v| v.as_array()) {
+        return coerce_enum(value, conversion, variants, defs);
+    }
+
+    match conversion {
+        Conversion::Types => {
+            let types: Vec<&str> = match sch.get("type") {
+                Some(Value::String(typ)) => vec![typ.as_str()],
+                Some(Value::Array(types)) => types.iter().filter_map(|t| t.as_str()).collect(),
+                _ => Vec::new(),
+            };
+
+            if let Value::String(s) = value
+                && let Some(v) = convert_string(s, &types)
+            {
+                *value = v;
+            }
+        }
+        Conversion::Names => {
+            if let Some(props) = sch.get("properties").and_then(|p| p.as_object()) {
+                drop_struct_name(value, props);
+            }
+        }
+        Conversion::None => {}
+    }
+
+    match value {
+        Value::Object(obj) => {
+            let props = sch.get("properties");
+            let additional = sch.get("additionalProperties");
+            for (pname, pvalue) in obj {
+                if let Some(psch) = props.and_then(|p| p.get(pname)).or(additional) {
+                    coerce(pvalue, conversion, psch, defs);
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(isch) = sch.get("items") {
+                for item in items {
+                    coerce(item, conversion, isch, defs);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+fn coerce_enum(value: &mut Value, conversion: Conversion, variants: &[Value], defs: &Value) {
+    let Some(tag_property) = crate::merge_with_defaults::get_enum_tag_property_name(variants)
+    else {
+        // Not an internally tagged enum, e.g. enum of strings
+        return;
+    };
+
+    if conversion == Conversion::Names {
+        name_to_tag(value, tag_property, variants);
+    }
+
+    let Some(tag) = value.get(tag_property) else {
+        return;
+    };
+
+    if let Some(vsch) = variants
+        .iter()
+        .find(|v| v["properties"][tag_property]["const"] == *tag)
+    {
+        coerce(value, conversion, vsch, defs);
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Maps `"Serial"`, `{"Serial": null}` and `{"Postgres": {...}}` onto `{"kind": "Serial"}`
+/// and `{"kind": "Postgres", ...}`
+fn name_to_tag(value: &mut Value, tag_property: &str, variants: &[Value]) {
+    let is_variant = |name: &str| {
+        variants
+            .iter()
+            .any(|v| v["properties"][tag_property]["const"] == name)
+    };
+
+    let (name, fields) = match value {
+        Value::String(name) if is_variant(name) => (name.clone(), serde_json::Map::new()),
+        Value::Object(obj) if obj.len() == 1 => {
+            let Some((name, fields)) = obj.iter().next() else {
+                return;
+            };
+            if name == tag_property || !is_variant(name) {
+                return;
+            }
+            let fields = match fields {
+                Value::Object(fields) => fields.clone(),
+                Value::Null => serde_json::Map::new(),
+                Value::Array(items) if items.is_empty() => serde_json::Map::new(),
+                // Not a struct variant, left to be reported when deserializing
+                _ => return,
+            };
+            (name.clone(), fields)
+        }
+        _ => return,
+    };
+
+    let mut tagged = serde_json::Map::new();
+    tagged.insert(tag_property.to_string(), Value::String(name));
+    tagged.extend(fields);
+    *value = Value::Object(tagged);
+}
+
+/// Unwraps `{"Config": {...}}` when `Config` is not a property of the struct
+fn drop_struct_name(value: &mut Value, props: &serde_json::Map<String, Value>) {
+    let Value::Object(obj) = value else {
+        return;
+    };
+    if obj.len() != 1 {
+        return;
+    }
+    let Some((name, Value::Object(fields))) = obj.iter_mut().next() else {
+        return;
+    };
+    if props.contains_key(name) {
+        return;
+    }
+    *value = Value::Object(std::mem::take(fields));
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+fn convert_string(s: &str, types: &[&str]) -> Option<Value> {
+    if types.is_empty() || types.contains(&"string") {
+        return None;
+    }
+
+    for typ in types {
+        let value = match *typ {
+            "boolean" => match s {
+                "true" => Some(Value::Bool(true)),
+                "false" => Some(Value::Bool(false)),
+                _ => None,
+            },
+            "integer" => parse_integer(s),
+            "number" => parse_integer(s)
+                .or_else(|| serde_json::Number::from_f64(s.parse().ok()?).map(Value::Number)),
+            "null" if s.is_empty() => Some(Value::Null),
+            "array" if s.starts_with('[') => serde_json::from_str(s).ok(),
+            "object" if s.starts_with('{') => serde_json::from_str(s).ok(),
+            _ => None,
+        };
+        if value.is_some() {
+            return value;
+        }
+    }
+
+    None
+}
+
+fn parse_integer(s: &str) -> Option<Value> {
+    if let Ok(v) = s.parse::<i64>() {
+        return Some(v.into());
+    }
+    s.parse::<u64>().ok().map(Value::from)
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
@@ -1,12 +1,13 @@
 #![allow(unused)]
 
-use std::{marker::PhantomData, path::Path, rc::Rc};
+use std::{marker::PhantomData, path::Path, rc::Rc, sync::Arc};
 
 use crate::Value;
 use crate::combine::Combine;
-use crate::errors::{ReadError, WriteError};
-use crate::format::Format;
-use crate::source::Source;
+use crate::errors::{Location, PathError, ReadError, WriteError};
+use crate::format::{Conversion, Format, FormatAdapter};
+use crate::path::{KeyPath, Segment};
+use crate::source::{Layer, Source};
 
 /////////////////////////////////////////////////////////////////////////////////////////
 
@@ -24,6 +25,9 @@ pub type OnDeprecatedClb =
 pub struct Config<Cfg> {
     sources: Vec<Box<dyn Source>>,
     deprecation_clb: Option<Box<OnDeprecatedClb>>,
+    all_errors: bool,
+    #[cfg(feature = "watch")]
+    watch_debounce: std::time::Duration,
     _p: PhantomData<Cfg>,
 }
 
@@ -34,6 +38,9 @@ impl<Cfg> Default for Config<Cfg> {
         Self {
             sources: Vec::new(),
             deprecation_clb: None,
+            all_errors: false,
+            #[cfg(feature = "watch")]
+            watch_debounce: crate::watch::DEFAULT_DEBOUNCE,
             _p: PhantomData,
         }
     }
@@ -70,22 +77,82 @@ where
     /// Deserializes the marged config into the config type
     #[cfg(not(feature = "derive-jsonschema"))]
     pub fn extract(&self) -> Result<Cfg, ReadError> {
-        let value = self.data_combined(None)?;
-        serde_json::from_value(value).map_err(|e| ReadError::Serde(e.into()))
+        self.extract_layers(self.load_layers(None)?)
     }
 
-    fn data_combined(
+    #[cfg(not(feature = "derive-jsonschema"))]
+    fn extract_layers(&self, layers: Vec<Layer>) -> Result<Cfg, ReadError> {
+        let value = Self::combine_layers(layers.iter().map(|l| l.value.clone()));
+        crate::locate::deserialize(value, &layers)
+    }
+
+    /// Extracts the config created by `build` and returns a handle that builds and
+    /// extracts it again in background when any of the files of the sources change
+    /// (see [`Source::watch_paths()`]), e.g.
+    /// `Config::watch(|| Config::new().with_source(File::<Yaml>::new("config.yaml")))`.
+    ///
+    /// The config is built on the reloading thread, so only `build` has to be
+    /// `Send + Sync`, while sources and callbacks don't.
+    #[cfg(all(feature = "watch", not(feature = "derive-jsonschema")))]
+    pub fn watch(
+        build: impl Fn() -> Self + Send + Sync + 'static,
+    ) -> Result<crate::watch::Live<Cfg>, ReadError>
+    where
+        Cfg: Send + Sync + 'static,
+    {
+        let config = build();
+        let initial = (config.extract(), config.watch_paths());
+        crate::watch::Live::spawn(
+            initial,
+            move || {
+                let config = build();
+                (config.extract(), config.watch_paths())
+            },
+            config.watch_debounce,
+        )
+    }
+
+    /// Sets how long [`Self::watch()`] waits for file changes to settle before
+    /// reloading, defaults to [`crate::watch::DEFAULT_DEBOUNCE`]
+    #[cfg(feature = "watch")]
+    pub fn with_watch_debounce(mut self, debounce: std::time::Duration) -> Self {
+        self.watch_debounce = debounce;
+        self
+    }
+
+    /// Compares two configs, see [`crate::diff()`]
+    #[cfg(all(feature = "derive-serialize", not(feature = "derive-jsonschema")))]
+    pub fn diff(&self, old: &Cfg, new: &Cfg) -> Result<crate::diff::Diff, ReadError>
+    where
+        Cfg: serde::Serialize,
+    {
+        let to_value = |cfg| serde_json::to_value(cfg).map_err(|e| ReadError::Serde(e.into()));
+        Ok(crate::diff::diff(&to_value(old)?, &to_value(new)?))
+    }
+
+    #[cfg(feature = "watch")]
+    fn watch_paths(&self) -> Vec<std::path::PathBuf> {
+        self.sources.iter().flat_map(|s| s.watch_paths()).collect()
+    }
+
+    fn load_layers(
         &self,
         extra_source: Option<&dyn crate::source::Source>,
-    ) -> Result<Value, ReadError> {
-        let mut combined = Value::Object(Default::default());
+    ) -> Result<Vec<Layer>, ReadError> {
+        let mut layers = Vec::new();
 
         for source in self.sources.iter().map(|b| b.as_ref()).chain(extra_source) {
-            let Some(new) = source.load()? else {
-                continue;
-            };
+            layers.extend(source.load_layers()?);
+        }
+
+        Ok(layers)
+    }
+
+    fn combine_layers(values: impl IntoIterator<Item = Value>) -> Value {
+        let mut combined = Value::Object(Default::default());
 
-            combined = if combined.as_object().unwrap().is_empty() {
+        for new in values {
+            combined = if combined.as_object().is_some_and(|o| o.is_empty()) {
                 new
             } else {
                 Cfg::merge(&mut combined, new);
@@ -93,26 +160,102 @@ where
             };
         }
 
-        Ok(combined)
+        combined
     }
 
     /// Returns raw merged data
     #[cfg(not(feature = "derive-jsonschema"))]
     pub fn data(&self, with_defaults: bool) -> Result<Value, ReadError> {
+        Ok(self.data_layers(with_defaults)?.0)
+    }
+
+    #[cfg(not(feature = "derive-jsonschema"))]
+    fn data_layers(&self, with_defaults: bool) -> Result<(Value, Vec<Layer>), ReadError> {
         if with_defaults {
-            panic!("Merging with default currently requires `setty/derive-jsonschema` feature")
+            return Err(ReadError::FeatureRequired {
+                operation: "Merging with defaults",
+                feature: "derive-jsonschema",
+            });
         }
-        self.data_combined(None)
+        let layers = self.load_layers(None)?;
+        let value = Self::combine_layers(layers.iter().map(|l| l.value.clone()));
+        Ok((value, layers))
     }
 
     /// Returns value under specified path
     #[cfg(not(feature = "derive-jsonschema"))]
     pub fn get_value(&self, path: &str, with_defaults: bool) -> Result<Option<Value>, ReadError> {
+        let path = KeyPath::parse(path)?;
         let data = self.data(with_defaults)?;
-        Ok(Self::find_value(path, data.into()))
+        Ok(path.select(&data))
+    }
+
+    /// Returns value under specified path deserialized into `T`, errors name the full
+    /// path of the offending value
+    #[cfg(not(feature = "derive-jsonschema"))]
+    pub fn get<T>(&self, path: &str, with_defaults: bool) -> Result<Option<T>, ReadError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let path = KeyPath::parse(path)?;
+        let (data, layers) = self.data_layers(with_defaults)?;
+        let Some(value) = path.select(&data) else {
+            return Ok(None);
+        };
+        crate::locate::deserialize_at(value, &path, &layers).map(Some)
+    }
+
+    /// Deserializes only the section of the merged config under specified path, e.g.
+    /// `extract_at::<DatabaseConfig>("database")`
+    #[cfg(not(feature = "derive-jsonschema"))]
+    pub fn extract_at<T>(&self, path: &str) -> Result<T, ReadError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let path = Self::section_path(path)?;
+        let (data, layers) = self.data_layers(false)?;
+        let Some(value) = path.select(&data) else {
+            return Err(ReadError::MissingField {
+                path: path.to_string(),
+                location: None,
+            });
+        };
+        crate::locate::deserialize_at(value, &path, &layers)
+    }
+
+    /// Values can only be converted using the schema
+    #[cfg(not(feature = "derive-jsonschema"))]
+    fn coerce_value(&self, value: &mut Value, conversion: Conversion) {}
+
+    fn section_path(path: &str) -> Result<KeyPath, ReadError> {
+        let path = KeyPath::parse(path)?;
+        if path.has_wildcards() {
+            return Err(crate::errors::PathError::Unsupported {
+                path: path.to_string(),
+                reason: "sections can't be extracted by wildcards, use `get()` instead",
+            }
+            .into());
+        }
+        Ok(path)
     }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
 
-    /// Sets the value under specified path creating new or merging it into existing config file
+/// Editing of config files. Edits are checked by extracting the config merged from all
+/// sources with the edited file as it would be written, so nothing is written to disk
+/// if the config can't be read back.
+#[cfg(feature = "derive-deserialize")]
+impl<
+    #[cfg(not(feature = "derive-jsonschema"))] Cfg: serde::de::DeserializeOwned + Combine,
+    #[cfg(all(feature = "derive-jsonschema", not(feature = "derive-validate")))] Cfg: serde::de::DeserializeOwned + schemars::JsonSchema + Combine,
+    #[cfg(all(feature = "derive-jsonschema", feature = "derive-validate"))] Cfg: serde::de::DeserializeOwned + schemars::JsonSchema + Combine + validator::Validate,
+> Config<Cfg>
+{
+    /// Sets the value under specified path creating new or merging it into existing config file.
+    ///
+    /// Paths with indices and wildcards (see [`KeyPath`]) replace matching entries that
+    /// already exist in the file.
     pub fn set_value<Fmt>(
         &self,
         path: &str,
@@ -129,89 +272,273 @@ where
     fn set_value_impl<Fmt>(
         &self,
         path: &str,
-        mut value: Value,
+        value: Value,
         in_config_path: &Path,
     ) -> Result<(), WriteError>
     where
         Fmt: 'static,
         Fmt: Format,
     {
-        // Nest value under the path
-        for segment in path.rsplit('.') {
-            let mut map = serde_json::Map::new();
-            map.insert(segment.to_string(), value);
-            value = map.into();
+        let path = KeyPath::parse(path).map_err(ReadError::from)?;
+
+        if !path.is_keys_only() {
+            return self.set_value_in_place::<Fmt>(&path, value, in_config_path);
         }
 
-        // Deserialize config merged with new values to validate before writing it to disk
-        // TODO: Too much cloning
-        let data = self.data_combined(Some(&value.clone()))?;
-        serde_json::from_value::<Cfg>(value.clone()).map_err(|e| ReadError::Serde(e.into()))?;
+        // Nest value under the path and merge it into the file the same way sources are
+        let mut nested = Value::Null;
+        path.set(&mut nested, value).map_err(ReadError::from)?;
 
-        let content = if !in_config_path.is_file() {
-            if let Some(dir) = in_config_path.parent() {
-                std::fs::create_dir_all(dir)?;
-            }
-            Fmt::serialize(&value).unwrap()
-        } else {
-            // Read target config merged with the new value
-            let merged = Self::new()
-                .with_source(crate::source::File::<Fmt>::new(in_config_path))
-                .with_source(value.clone())
-                .data_combined(None)?;
+        self.edit_file::<Fmt, _>(in_config_path, |target| {
+            *target = Self::combine_layers([std::mem::take(target), nested]);
+            Ok(())
+        })
+    }
 
-            Fmt::serialize(&merged).unwrap()
+    /// Indices and wildcards address entries that already exist in the file, so instead
+    /// of merging (that would e.g. append to arrays) the value is set directly
+    fn set_value_in_place<Fmt>(
+        &self,
+        path: &KeyPath,
+        value: Value,
+        in_config_path: &Path,
+    ) -> Result<(), WriteError>
+    where
+        Fmt: 'static,
+        Fmt: Format,
+    {
+        self.edit_file::<Fmt, _>(in_config_path, |target| path.set(target, value))
+    }
+
+    /// Appends the value to the array under specified path in the config file, creating
+    /// the array if it doesn't exist.
+    ///
+    /// Path can contain wildcards (see [`KeyPath`]) to append to multiple arrays.
+    pub fn append_value<Fmt>(
+        &self,
+        path: &str,
+        value: impl Into<Value>,
+        in_config_path: impl AsRef<Path>,
+    ) -> Result<(), WriteError>
+    where
+        Fmt: 'static,
+        Fmt: Format,
+    {
+        let path = KeyPath::parse(path).map_err(ReadError::from)?;
+        let value = value.into();
+
+        self.edit_file::<Fmt, _>(in_config_path.as_ref(), |target| {
+            path.update(target, |apath, array| {
+                let items = Self::as_array_mut(apath, array)?;
+                items.push(value.clone());
+                Ok(())
+            })
+        })
+    }
+
+    /// Inserts the value into the array under specified path in the config file at
+    /// `index`, shifting the following elements
+    pub fn insert_value<Fmt>(
+        &self,
+        path: &str,
+        index: usize,
+        value: impl Into<Value>,
+        in_config_path: impl AsRef<Path>,
+    ) -> Result<(), WriteError>
+    where
+        Fmt: 'static,
+        Fmt: Format,
+    {
+        let path = KeyPath::parse(path).map_err(ReadError::from)?;
+        let value = value.into();
+
+        self.edit_file::<Fmt, _>(in_config_path.as_ref(), |target| {
+            path.update(target, |apath, array| {
+                let items = Self::as_array_mut(apath, array)?;
+                if index > items.len() {
+                    return Err(PathError::IndexOutOfBounds {
+                        path: apath.to_string(),
+                        index,
+                        len: items.len(),
+                    });
+                }
+                items.insert(index, value.clone());
+                Ok(())
+            })
+        })
+    }
+
+    /// Removes the element at `index` from the array under specified path in the config
+    /// file, shifting the following elements.
+    ///
+    /// Returns the removed elements, one per array matched by the path.
+    pub fn remove_value<Fmt>(
+        &self,
+        path: &str,
+        index: usize,
+        in_config_path: impl AsRef<Path>,
+    ) -> Result<Vec<Value>, WriteError>
+    where
+        Fmt: 'static,
+        Fmt: Format,
+    {
+        let path = KeyPath::parse(path).map_err(ReadError::from)?;
+
+        self.edit_file::<Fmt, _>(in_config_path.as_ref(), |target| {
+            let mut removed = Vec::new();
+            path.update(target, |apath, array| {
+                let items = Self::as_array_mut(apath, array)?;
+                if index >= items.len() {
+                    return Err(PathError::IndexOutOfBounds {
+                        path: apath.to_string(),
+                        index,
+                        len: items.len(),
+                    });
+                }
+                removed.push(items.remove(index));
+                Ok(())
+            })?;
+            Ok(removed)
+        })
+    }
+
+    /// Missing arrays are treated as empty
+    fn as_array_mut<'a>(
+        path: &KeyPath,
+        value: &'a mut Value,
+    ) -> Result<&'a mut Vec<Value>, PathError> {
+        if value.is_null() {
+            *value = Value::Array(Vec::new());
+        }
+        match value {
+            Value::Array(items) => Ok(items),
+            value => Err(PathError::invalid_type(path, "array", value)),
+        }
+    }
+
+    /// Applies the edit to the value of the config file, checks the config merged with
+    /// the result and writes the file, creating it if doesn't exist
+    fn edit_file<Fmt, R>(
+        &self,
+        in_config_path: &Path,
+        edit: impl FnOnce(&mut Value) -> Result<R, PathError>,
+    ) -> Result<R, WriteError>
+    where
+        Fmt: 'static,
+        Fmt: Format,
+    {
+        let (original, mut target) = if in_config_path.is_file() {
+            let (original, mut target) = Self::read_file::<Fmt>(in_config_path)?;
+            self.coerce_value(&mut target, Fmt::conversion());
+            (Some(original), target)
+        } else {
+            (None, Value::Object(Default::default()))
         };
 
+        let ret = edit(&mut target).map_err(ReadError::from)?;
+
+        let content = match &original {
+            Some(original) => Fmt::update(original, &target),
+            None => Fmt::serialize(&target),
+        }
+        .map_err(|e| WriteError::Serialize(e.into()))?;
+
+        self.check_edit::<Fmt>(in_config_path, target, &content)?;
+
+        if original.is_none()
+            && let Some(dir) = in_config_path.parent()
+        {
+            std::fs::create_dir_all(dir)?;
+        }
         std::fs::write(in_config_path, content)?;
-        Ok(())
+        Ok(ret)
     }
 
-    // TODO: Validate new config before writing?
+    /// Removes the value under specified path (see [`KeyPath`]) from the config file,
+    /// returning the removed value
     pub fn unset_value<Fmt>(
         &self,
         path: &str,
         in_config_path: &Path,
     ) -> Result<Option<Value>, WriteError>
     where
+        Fmt: 'static,
         Fmt: Format,
     {
-        let data = std::fs::read(in_config_path)?;
-        let data = str::from_utf8(&data).unwrap();
-        let mut value: Value = Fmt::deserialize(data).map_err(|e| ReadError::Serde(e.into()))?;
+        let path = KeyPath::parse(path).map_err(ReadError::from)?;
+        let (data, mut value) = Self::read_file::<Fmt>(in_config_path)?;
+        self.coerce_value(&mut value, Fmt::conversion());
 
-        let prev_value = Self::unset_rec(path, value.as_object_mut());
+        let prev_value = path.remove(&mut value);
 
-        let new_data = Fmt::serialize(&value).unwrap();
+        let new_data = Fmt::update(&data, &value).map_err(|e| WriteError::Serialize(e.into()))?;
+
+        self.check_edit::<Fmt>(in_config_path, value, &new_data)?;
 
         std::fs::write(in_config_path, new_data)?;
 
         Ok(prev_value)
     }
 
-    fn unset_rec(path: &str, obj: Option<&mut serde_json::Map<String, Value>>) -> Option<Value> {
-        let obj = obj?;
-
-        if let Some((head, tail)) = path.split_once('.') {
-            Self::unset_rec(tail, obj.get_mut(head).and_then(|v| v.as_object_mut()))
-        } else {
-            obj.remove(path)
+    /// Extracts the config from all sources where layers of the edited file are replaced
+    /// with its new value (or added last if no source reads it), so that the edit is
+    /// deserialized, validated and checked for deprecations in the context of the
+    /// merged config, e.g. required fields can live in other files
+    fn check_edit<Fmt>(
+        &self,
+        in_config_path: &Path,
+        value: Value,
+        text: &str,
+    ) -> Result<(), ReadError>
+    where
+        Fmt: 'static,
+        Fmt: Format,
+    {
+        let mut edited = Some(Layer {
+            name: in_config_path.display().to_string().into(),
+            value,
+            text: Some(text.into()),
+            format: Some(Arc::new(FormatAdapter::<Fmt>::new())),
+            conversion: Fmt::conversion(),
+        });
+
+        let mut layers = Vec::new();
+        for source in &self.sources {
+            // Files referred to by the source, e.g. via `!include`, follow its own file
+            if !source
+                .watch_paths()
+                .first()
+                .is_some_and(|p| is_same_file(p, in_config_path))
+            {
+                layers.extend(source.load_layers()?);
+            } else if let Some(mut layer) = edited.take() {
+                layer.name = source.name();
+                layers.push(layer);
+            }
         }
-    }
+        layers.extend(edited);
 
-    fn find_value(path: &str, data: Value) -> Option<Value> {
-        let mut current = data;
+        self.extract_layers(layers)?;
+        Ok(())
+    }
 
-        for segment in path.split(".") {
-            let mut mamp = match current {
-                Value::Object(v) => v,
-                _ => return None,
+    fn read_file<Fmt>(path: &Path) -> Result<(String, Value), WriteError>
+    where
+        Fmt: Format,
+    {
+        let data = std::fs::read_to_string(path)?;
+        let value: Value = Fmt::deserialize(&data).map_err(|e| {
+            let name = path.display().to_string();
+            let location = match Fmt::error_position(&e, &data) {
+                Some((line, col)) => Location::at(name, &data, line, col),
+                None => Location::new(name),
             };
-            let child = mamp.remove(segment)?;
-            current = child;
-        }
-
-        Some(current)
+            ReadError::Syntax {
+                location,
+                source: e.into(),
+            }
+        })?;
+        Ok((data, value))
     }
 }
 
@@ -233,9 +560,22 @@ impl<
         self
     }
 
+    /// Makes [`Self::extract`] report all unknown and missing fields, type mismatches and
+    /// validation failures at once as [`ReadError::Multiple`] instead of stopping at the
+    /// first one, so all mistakes can be fixed in one go.
+    pub fn with_all_errors(mut self, all_errors: bool) -> Self {
+        self.all_errors = all_errors;
+        self
+    }
+
     /// Deserializes the marged config into the config type and performs deprecation checks and validation.
     pub fn extract(&self) -> Result<Cfg, ReadError> {
-        let value = self.data_combined(None)?;
+        self.extract_layers(self.load_layers(None)?)
+    }
+
+    fn extract_layers(&self, mut layers: Vec<Layer>) -> Result<Cfg, ReadError> {
+        self.coerce_layers(&mut layers);
+        let value = Self::combine_layers(layers.iter().map(|l| l.value.clone()));
 
         // TODO: Maybe check per every source, not after combining?
         let clb = match self.deprecation_clb.as_deref() {
@@ -246,20 +586,269 @@ impl<
         let schema = self.json_schema().to_value();
         crate::check_deprecated::check_deprecated_fields(&schema, &value, clb);
 
-        let cfg = serde_json::from_value(value).map_err(|e| ReadError::Serde(e.into()))?;
+        if !self.all_errors {
+            let cfg = crate::locate::deserialize(value, &layers)
+                .map_err(|e| Self::suggest_paths(e, &self.complete_path("")))?;
+
+            #[cfg(feature = "derive-validate")]
+            validator::Validate::validate(&cfg)?;
+
+            return Ok(cfg);
+        }
+
+        let cfg = match crate::locate::deserialize(value.clone(), &layers) {
+            Ok(cfg) => cfg,
+            Err(err) if err.path().is_none() => return Err(err),
+            Err(err) => {
+                let mut errors = crate::check_schema::check_schema(&schema, &value, &layers);
+
+                // Schema is less strict than the types, so keep the error if it's not covered
+                let covered = errors.iter().any(|e| {
+                    let (Some(a), Some(b)) = (e.path(), err.path()) else {
+                        return false;
+                    };
+                    is_path_prefix(a, b) || is_path_prefix(b, a)
+                });
+                if !covered {
+                    errors.push(err);
+                }
+
+                #[cfg(feature = "derive-validate")]
+                Self::validate_best_effort(value, &layers, &mut errors);
+
+                return Err(Self::suggest_paths(
+                    ReadError::Multiple(errors),
+                    &self.complete_path(""),
+                ));
+            }
+        };
 
         #[cfg(feature = "derive-validate")]
-        validator::Validate::validate(&cfg)?;
+        if let Err(errs) = validator::Validate::validate(&cfg) {
+            let mut errors = Vec::new();
+            crate::locate::flatten_validation_errors(&mut Vec::new(), &errs, &layers, &mut errors);
+            return Err(ReadError::Multiple(errors));
+        }
 
         Ok(cfg)
     }
 
+    /// Validates the value with the invalid entries left out, adding failures at paths
+    /// that have no errors yet. Reports [`ReadError::ValidationSkipped`] when the value
+    /// still can't be deserialized, e.g. when a required field is invalid or missing.
+    #[cfg(feature = "derive-validate")]
+    fn validate_best_effort(mut value: Value, layers: &[Layer], errors: &mut Vec<ReadError>) {
+        let cfg = loop {
+            let err = match crate::locate::deserialize::<Cfg>(value.clone(), &[]) {
+                Ok(cfg) => break cfg,
+                Err(err) => err,
+            };
+            let removed = match &err {
+                ReadError::UnknownField { path, .. }
+                | ReadError::InvalidType { path, .. }
+                | ReadError::InvalidValue { path, .. }
+                | ReadError::UnknownVariant { path, .. }
+                | ReadError::Deserialize { path, .. } => KeyPath::parse(path)
+                    .ok()
+                    .and_then(|path| path.remove(&mut value)),
+                _ => None,
+            };
+            if removed.is_none() {
+                errors.push(ReadError::ValidationSkipped);
+                return;
+            }
+        };
+
+        let Err(errs) = validator::Validate::validate(&cfg) else {
+            return;
+        };
+
+        let mut failures = Vec::new();
+        crate::locate::flatten_validation_errors(&mut Vec::new(), &errs, layers, &mut failures);
+
+        failures.retain(|f| {
+            !errors.iter().any(|e| {
+                let (Some(a), Some(b)) = (e.path(), f.path()) else {
+                    return false;
+                };
+                is_path_prefix(a, b) || is_path_prefix(b, a)
+            })
+        });
+        errors.extend(failures);
+    }
+
+    /// Extracts the config created by `build` and returns a handle that builds and
+    /// extracts it again in background when any of the files of the sources change
+    /// (see [`Source::watch_paths()`]), e.g.
+    /// `Config::watch(|| Config::new().with_source(File::<Yaml>::new("config.yaml")))`.
+    ///
+    /// The config is built on the reloading thread, so only `build` has to be
+    /// `Send + Sync`, while sources and callbacks don't.
+    #[cfg(feature = "watch")]
+    pub fn watch(
+        build: impl Fn() -> Self + Send + Sync + 'static,
+    ) -> Result<crate::watch::Live<Cfg>, ReadError>
+    where
+        Cfg: Send + Sync + 'static,
+    {
+        let config = build();
+        let initial = (config.extract(), config.watch_paths());
+        crate::watch::Live::spawn(
+            initial,
+            move || {
+                let config = build();
+                (config.extract(), config.watch_paths())
+            },
+            config.watch_debounce,
+        )
+    }
+
+    /// Compares two configs, reporting switches between enum variants as
+    /// [`crate::diff::ChangeKind::VariantChanged`]
+    #[cfg(feature = "derive-serialize")]
+    pub fn diff(&self, old: &Cfg, new: &Cfg) -> Result<crate::diff::Diff, ReadError>
+    where
+        Cfg: serde::Serialize,
+    {
+        let to_value = |cfg| serde_json::to_value(cfg).map_err(|e| ReadError::Serde(e.into()));
+        Ok(crate::diff::diff_with_schema(
+            &self.json_schema(),
+            &to_value(old)?,
+            &to_value(new)?,
+        ))
+    }
+
+    /// Replaces suggestions of unknown field errors with similar paths of the schema
+    fn suggest_paths(err: ReadError, paths: &[String]) -> ReadError {
+        match err {
+            ReadError::UnknownField {
+                path,
+                expected,
+                suggestions,
+                location,
+            } => {
+                let suggestions = crate::locate::suggest_paths(&path, paths).unwrap_or(suggestions);
+                ReadError::UnknownField {
+                    path,
+                    expected,
+                    suggestions,
+                    location,
+                }
+            }
+            ReadError::Multiple(errors) => ReadError::Multiple(
+                errors
+                    .into_iter()
+                    .map(|e| Self::suggest_paths(e, paths))
+                    .collect(),
+            ),
+            err => err,
+        }
+    }
+
+    /// Deserializes only the section of the merged config under specified path, e.g.
+    /// `extract_at::<DatabaseConfig>("database")`, performing deprecation checks and
+    /// validation of that section.
+    ///
+    /// Defaults of the parent sections are taken into account, so a section that is
+    /// missing from all sources but has a default can be extracted too.
+    pub fn extract_at<
+        #[cfg(not(feature = "derive-validate"))] T: serde::de::DeserializeOwned,
+        #[cfg(feature = "derive-validate")] T: serde::de::DeserializeOwned + validator::Validate,
+    >(
+        &self,
+        path: &str,
+    ) -> Result<T, ReadError> {
+        let path = Self::section_path(path)?;
+        let (data, layers) = self.data_layers(true)?;
+        let Some(value) = path.select(&data) else {
+            return Err(ReadError::MissingField {
+                path: path.to_string(),
+                location: None,
+            });
+        };
+
+        let clb = match self.deprecation_clb.as_deref() {
+            Some(clb) => clb,
+            None => &crate::check_deprecated::default_deprecation_clb as &OnDeprecatedClb,
+        };
+
+        // Report only the deprecated fields within the section
+        let in_section = |dpath: &[&str]| {
+            dpath.len() >= path.segments().len()
+                && path.segments().iter().zip(dpath).all(|(s, k)| match s {
+                    Segment::Key(key) => key == k,
+                    Segment::Wildcard => true,
+                    Segment::Index(_) => false,
+                })
+        };
+        let schema = self.json_schema().to_value();
+        crate::check_deprecated::check_deprecated_fields(
+            &schema,
+            &data,
+            &|dpath: &[&str], reason: Option<&str>, since: Option<&str>| {
+                if in_section(dpath) {
+                    clb(dpath, reason, since)
+                }
+            },
+        );
+
+        let section: T = crate::locate::deserialize_at(value, &path, &layers)
+            .map_err(|e| Self::suggest_paths(e, &self.complete_path("")))?;
+
+        #[cfg(feature = "derive-validate")]
+        if let Err(errs) = validator::Validate::validate(&section) {
+            if !self.all_errors {
+                return Err(errs.into());
+            }
+            let mut segments = crate::locate::segments(&path);
+            let mut errors = Vec::new();
+            crate::locate::flatten_validation_errors(&mut segments, &errs, &layers, &mut errors);
+            return Err(ReadError::Multiple(errors));
+        }
+
+        Ok(section)
+    }
+
     /// Returns raw merged data
     pub fn data(&self, with_defaults: bool) -> Result<Value, ReadError> {
-        let mut value = self.data_combined(None)?;
+        Ok(self.data_layers(with_defaults)?.0)
+    }
+
+    /// Converts values of layers that don't match the config types directly (see
+    /// [`Format::conversion()`]), so that they can be merged with values of other layers
+    fn coerce_layers(&self, layers: &mut [Layer]) {
+        if layers.iter().all(|l| l.conversion == Conversion::None) {
+            return;
+        }
+
+        let schema = self.json_schema().to_value();
+        let null = Value::Null;
+        let defs = schema.get("$defs").unwrap_or(&null);
+
+        for layer in layers {
+            crate::coerce::coerce(&mut layer.value, layer.conversion, &schema, defs);
+        }
+    }
+
+    /// Same as [`Self::coerce_layers()`] for a single value, e.g. of a file being edited
+    fn coerce_value(&self, value: &mut Value, conversion: Conversion) {
+        if conversion == Conversion::None {
+            return;
+        }
+
+        let schema = self.json_schema().to_value();
+        let null = Value::Null;
+        let defs = schema.get("$defs").unwrap_or(&null);
+        crate::coerce::coerce(value, conversion, &schema, defs);
+    }
+
+    fn data_layers(&self, with_defaults: bool) -> Result<(Value, Vec<Layer>), ReadError> {
+        let mut layers = self.load_layers(None)?;
+        self.coerce_layers(&mut layers);
+        let mut value = Self::combine_layers(layers.iter().map(|l| l.value.clone()));
 
         if !with_defaults {
-            return Ok(value);
+            return Ok((value, layers));
         }
 
         // Get schema that has all variants and defaults
@@ -269,19 +858,36 @@ impl<
 
         // Begin the merge-aroo!
         crate::merge_with_defaults::merge_with_defaults(
-            schema["title"].as_str().unwrap(),
+            schema["title"].as_str().unwrap_or_default(),
             &mut value,
             &schema,
             defs,
         );
 
-        Ok(value)
+        Ok((value, layers))
     }
 
     /// Returns value under specified path
     pub fn get_value(&self, path: &str, with_defaults: bool) -> Result<Option<Value>, ReadError> {
+        let path = KeyPath::parse(path)?;
         let data = self.data(with_defaults)?;
-        Ok(Self::find_value(path, data))
+        Ok(path.select(&data))
+    }
+
+    /// Returns value under specified path deserialized into `T`, errors name the full
+    /// path of the offending value
+    pub fn get<T>(&self, path: &str, with_defaults: bool) -> Result<Option<T>, ReadError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let path = KeyPath::parse(path)?;
+        let (data, layers) = self.data_layers(with_defaults)?;
+        let Some(value) = path.select(&data) else {
+            return Ok(None);
+        };
+        crate::locate::deserialize_at(value, &path, &layers)
+            .map(Some)
+            .map_err(|e| Self::suggest_paths(e, &self.complete_path("")))
     }
 
     /// Returns JSON Schema describing the config type
@@ -289,22 +895,49 @@ impl<
         schemars::schema_for!(Cfg).into()
     }
 
+    /// Wraps the error into a report that renders snippets of the config files and help
+    /// text with descriptions of the offending fields from the schema
+    #[cfg(feature = "diagnostics")]
+    pub fn report(
+        &self,
+        error: impl Into<crate::errors::WriteError>,
+    ) -> crate::diagnostics::Report {
+        crate::diagnostics::Report::new(error).with_schema(&self.json_schema())
+    }
+
     /// Returns Markdown describing the config type
     pub fn markdown(&self) -> String {
         let schema = self.json_schema();
         crate::markdown::schema_to_markdown(&schema)
     }
 
+    /// Returns names, types, defaults, and descriptions of environment variables that
+    /// can set every leaf property of the config type when using [`crate::source::Env`]
+    /// source with the same `prefix` and `separator`.
+    ///
+    /// See `markdown::env_vars_to_markdown()` to render them as a table (requires
+    /// `gen-markdown` feature).
+    pub fn env_vars(&self, prefix: &str, separator: &str) -> Vec<crate::env_vars::EnvVar> {
+        let schema = self.json_schema().to_value();
+        crate::env_vars::schema_to_env_vars(&schema, prefix, separator)
+    }
+
     /// Given a prefix like `some.va` would return possible completions, e.g.
     /// `some.value` and `some.validator`
     pub fn complete_path(&self, prefix: &str) -> Vec<String> {
         let mut ret = Vec::new();
         let schema = self.json_schema();
-        let schema = schema.as_object().unwrap();
-        let defs = schema["$defs"].as_object().unwrap();
+        let Some(schema) = schema.as_object() else {
+            return ret;
+        };
+        let no_defs = serde_json::Map::new();
+        let defs = schema
+            .get("$defs")
+            .and_then(|v| v.as_object())
+            .unwrap_or(&no_defs);
 
         // TODO: PERF: This can be improved by considering prefix while traversing
-        Self::all_paths_rec("", schema, defs, &mut ret);
+        Self::all_paths_rec(&mut KeyPath::new(), schema, defs, &mut ret);
 
         ret.retain(|v| v.starts_with(prefix));
 
@@ -312,7 +945,7 @@ impl<
     }
 
     fn all_paths_rec(
-        path: &str,
+        path: &mut KeyPath,
         obj: &serde_json::Map<String, Value>,
         defs: &serde_json::Map<String, Value>,
         ret: &mut Vec<String>,
@@ -320,37 +953,68 @@ impl<
         let Some(properties) = obj.get("properties").and_then(|v| v.as_object()) else {
             if let Some(r) = obj.get("$ref").and_then(|v| v.as_str())
                 && let Some((_, name)) = r.rsplit_once('/')
+                && let Some(val) = defs.get(name).and_then(|v| v.as_object())
             {
-                let val = defs.get(name).unwrap().as_object().unwrap();
                 Self::all_paths_rec(path, val, defs, ret);
             }
             if let Some(one_of) = obj.get("oneOf").and_then(|v| v.as_array()) {
-                for var in one_of {
-                    Self::all_paths_rec(path, var.as_object().unwrap(), defs, ret);
+                for var in one_of.iter().filter_map(|v| v.as_object()) {
+                    Self::all_paths_rec(path, var, defs, ret);
                 }
             }
             if let Some(any_of) = obj.get("anyOf").and_then(|v| v.as_array()) {
-                for var in any_of {
-                    Self::all_paths_rec(path, var.as_object().unwrap(), defs, ret);
+                for var in any_of.iter().filter_map(|v| v.as_object()) {
+                    Self::all_paths_rec(path, var, defs, ret);
                 }
             }
+            // Elements of arrays and entries of maps, e.g. `servers.*.host`
+            if let Some(val) = obj
+                .get("items")
+                .or_else(|| obj.get("additionalProperties"))
+                .and_then(|v| v.as_object())
+            {
+                path.push(Segment::Wildcard);
+                Self::all_paths_rec(path, val, defs, ret);
+                path.pop();
+            }
             return;
         };
 
         for (name, val) in properties {
-            let ppath = if path.is_empty() {
-                name.clone()
-            } else {
-                format!("{path}.{name}")
-            };
+            path.push(Segment::Key(name.clone()));
 
-            ret.push(ppath.clone());
+            ret.push(path.to_string());
 
             if let Some(val) = val.as_object() {
-                Self::all_paths_rec(&ppath, val, defs, ret);
+                Self::all_paths_rec(path, val, defs, ret);
             }
+
+            path.pop();
         }
     }
 }
 
 /////////////////////////////////////////////////////////////////////////////////////////
+
+/// Whether both paths point to the same file, which may not exist yet
+#[cfg(feature = "derive-deserialize")]
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    a == b
+        || match (a.canonicalize(), b.canonicalize()) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Whether `prefix` is the same path as `path` or one of its parents
+#[cfg(feature = "derive-jsonschema")]
+fn is_path_prefix(prefix: &str, path: &str) -> bool {
+    prefix.is_empty()
+        || path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(['.', '[']))
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
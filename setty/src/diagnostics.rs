@@ -0,0 +1,366 @@
+#![cfg(feature = "diagnostics")]
+
+use owo_colors::{OwoColorize as _, Style};
+
+use crate::Value;
+use crate::errors::{ReadError, WriteError};
+use crate::path::{KeyPath, Segment};
+use crate::schema_helpers::resolve;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Human-friendly rendering of [`ReadError`] and [`WriteError`] for terminals, with
+/// snippets of the offending lines, labelled spans and help text, e.g.:
+/// ```text
+/// error: invalid type at `server.port`
+///  --> config.yaml:3:3
+///   |
+/// 3 |   port: eighty
+///   |   ^^^^ expected u16, found string "eighty"
+///   |
+///   = note: Port to listen on
+/// ```
+///
+/// Both `Display` and `Debug` render the report, so it can be returned from `main`:
+/// ```ignore
+/// fn main() -> Result<(), setty::diagnostics::Report> {
+///     let config = setty::Config::<AppConfig>::new().with_source(File::<Yaml>::new("config.yaml"));
+///     let cfg = config.extract().map_err(|e| config.report(e))?;
+///     Ok(())
+/// }
+/// ```
+///
+/// Attaching the schema (see [`Report::with_schema`] and [`crate::Config::report`]) adds
+/// descriptions and deprecation notes of the offending fields.
+pub struct Report(Box<ReportInner>);
+
+struct ReportInner {
+    error: WriteError,
+    schema: Option<Value>,
+    color: bool,
+}
+
+impl Report {
+    /// Creates a report that uses colors when `stderr` is a terminal and `NO_COLOR`
+    /// env var is not set
+    pub fn new(error: impl Into<WriteError>) -> Self {
+        use std::io::IsTerminal as _;
+
+        Self(Box::new(ReportInner {
+            error: error.into(),
+            schema: None,
+            color: std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+        }))
+    }
+
+    /// Enables or disables ANSI colors
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.0.color = color;
+        self
+    }
+
+    /// Uses the schema to add descriptions and deprecation notes of the offending fields
+    #[cfg(feature = "derive-jsonschema")]
+    pub fn with_schema(mut self, schema: &crate::schema::Schema) -> Self {
+        self.0.schema = Some(schema.as_value().clone());
+        self
+    }
+
+    pub fn error(&self) -> &WriteError {
+        &self.0.error
+    }
+
+    pub fn into_inner(self) -> WriteError {
+        self.0.error
+    }
+
+    /// Renders the report as a string
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        match &self.0.error {
+            WriteError::Read(err) => self.render_read(&mut out, err),
+            err => self.render_headline(&mut out, &err.to_string()),
+        }
+        out
+    }
+
+    fn render_read(&self, out: &mut String, err: &ReadError) {
+        let path = err.path();
+
+        let (headline, label) = match err {
+            ReadError::Syntax { location, source } => (
+                format!("failed to parse `{}`", location.source_name),
+                Some(source.to_string()),
+            ),
+            ReadError::UnknownField { path, .. } => (
+                format!("unknown field `{path}`"),
+                Some("unknown field".into()),
+            ),
+            ReadError::MissingField { path, .. } => {
+                let name = path.rsplit_once('.').map_or(path.as_str(), |(_, n)| n);
+                (
+                    format!("missing required field `{path}`"),
+                    Some(format!("`{name}` is missing")),
+                )
+            }
+            ReadError::InvalidType {
+                path,
+                expected,
+                found,
+                ..
+            } => (
+                format!("invalid type at `{path}`"),
+                Some(format!("expected {expected}, found {found}")),
+            ),
+            ReadError::InvalidValue {
+                path,
+                expected,
+                found,
+                ..
+            } => (
+                format!("invalid value at `{path}`"),
+                Some(format!("expected {expected}, found {found}")),
+            ),
+            ReadError::UnknownVariant {
+                path,
+                variant,
+                expected,
+                ..
+            } => (
+                format!("unknown variant `{variant}` at `{path}`"),
+                Some(format!("expected one of {}", quoted(expected))),
+            ),
+            ReadError::Deserialize { path, source, .. } => (
+                format!("failed to deserialize `{path}`"),
+                Some(source.to_string()),
+            ),
+            #[cfg(feature = "derive-validate")]
+            ReadError::FieldValidation { path, message, .. } => (
+                format!("validation failed at `{path}`"),
+                Some(message.clone()),
+            ),
+            ReadError::Multiple(errors) => {
+                for err in errors {
+                    self.render_read(out, err);
+                    out.push('\n');
+                }
+                let n = errors.len();
+                let s = if n == 1 { "" } else { "s" };
+                self.render_headline(out, &format!("found {n} error{s} in config"));
+                return;
+            }
+            err => (err.to_string(), None),
+        };
+
+        self.render_headline(out, &headline);
+
+        let mut notes = Vec::new();
+        if let ReadError::UnknownField {
+            suggestions,
+            expected,
+            ..
+        } = err
+        {
+            if !suggestions.is_empty() {
+                notes.push(("help", format!("did you mean {}?", quoted(suggestions))));
+                notes.extend(self.field_notes(&suggestions[0]));
+            } else if !expected.is_empty() {
+                notes.push(("help", format!("expected one of {}", quoted(expected))));
+            }
+        } else if let Some(path) = path {
+            notes.extend(self.field_notes(path));
+        }
+
+        let location = err.location();
+        let gutter = location
+            .and_then(|l| l.line)
+            .map_or(1, |line| line.to_string().len());
+        let pad = " ".repeat(gutter);
+
+        if let Some(location) = location {
+            let arrow = self.paint("-->", self.gutter_style());
+            out.push_str(&format!("\n{pad}{arrow} {location}"));
+        }
+
+        let snippet = location.and_then(|l| Some((l.line?, l.line_text.as_deref()?, l)));
+        let bar = self.paint("|", self.gutter_style());
+
+        if let Some((line, text, location)) = snippet {
+            let num = self.paint(&line.to_string(), self.gutter_style());
+            out.push_str(&format!("\n{pad} {bar}\n{num} {bar} {text}"));
+
+            if let Some(col) = location.col {
+                let prefix: String = text
+                    .chars()
+                    .take(col.saturating_sub(1))
+                    .map(|c| if c == '\t' { '\t' } else { ' ' })
+                    .collect();
+                let carets = "^".repeat(span_len(text, col));
+                let marker = match &label {
+                    Some(label) => format!("{carets} {}", first_line(label)),
+                    None => carets,
+                };
+                out.push_str(&format!(
+                    "\n{pad} {bar} {prefix}{}",
+                    self.paint(&marker, self.error_style())
+                ));
+            } else if let Some(label) = &label {
+                notes.insert(0, ("note", label.clone()));
+            }
+        } else if let Some(label) = &label {
+            notes.insert(0, ("note", label.clone()));
+        }
+
+        if !notes.is_empty() {
+            if snippet.is_some() {
+                out.push_str(&format!("\n{pad} {bar}"));
+            }
+            for (kind, text) in notes {
+                let kind = self.paint(kind, Style::new().bold());
+                let text = text.replace('\n', &format!("\n{pad}   "));
+                out.push_str(&format!("\n{pad} = {kind}: {text}"));
+            }
+        }
+
+        out.push('\n');
+    }
+
+    fn render_headline(&self, out: &mut String, headline: &str) {
+        out.push_str(&self.paint("error", self.error_style()));
+        out.push_str(&self.paint(&format!(": {headline}"), Style::new().bold()));
+    }
+
+    /// Description and deprecation notes of the field from the schema
+    fn field_notes(&self, path: &str) -> Vec<(&'static str, String)> {
+        let Some(schema) = &self.0.schema else {
+            return Vec::new();
+        };
+        let Some(field) = find_field(schema, path) else {
+            return Vec::new();
+        };
+
+        let mut notes = Vec::new();
+
+        if field.get("deprecated") == Some(&Value::Bool(true)) {
+            let mut note = format!("`{path}` is deprecated");
+            if let Some(since) = field["deprecation"]["since"].as_str() {
+                note.push_str(&format!(" since version {since}"));
+            }
+            if let Some(reason) = field["deprecation"]["reason"].as_str() {
+                note.push_str(&format!(": {reason}"));
+            }
+            notes.push(("warning", note));
+        }
+
+        let defs = &schema["$defs"];
+        let description = field["description"]
+            .as_str()
+            .or_else(|| resolve(field, defs)["description"].as_str());
+        if let Some(description) = description {
+            notes.push(("note", description.trim().to_string()));
+        }
+
+        notes
+    }
+
+    fn paint(&self, s: &str, style: Style) -> String {
+        if self.0.color {
+            s.style(style).to_string()
+        } else {
+            s.to_string()
+        }
+    }
+
+    fn error_style(&self) -> Style {
+        Style::new().red().bold()
+    }
+
+    fn gutter_style(&self) -> Style {
+        Style::new().blue().bold()
+    }
+}
+
+impl std::fmt::Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.render().trim_end())
+    }
+}
+
+impl std::fmt::Debug for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for Report {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0.error)
+    }
+}
+
+impl From<ReadError> for Report {
+    fn from(error: ReadError) -> Self {
+        Self::new(error)
+    }
+}
+
+impl From<WriteError> for Report {
+    fn from(error: WriteError) -> Self {
+        Self::new(error)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+fn quoted(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|n| format!("`{n}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn first_line(s: &str) -> &str {
+    s.lines().next().unwrap_or_default()
+}
+
+/// Length of the key or value token that starts at the one-based column
+fn span_len(text: &str, col: usize) -> usize {
+    let mut chars = text.chars().skip(col.saturating_sub(1)).peekable();
+
+    let len = match chars.peek() {
+        Some(&q @ ('"' | '\'')) => chars.skip(1).position(|c| c == q).map_or(1, |i| i + 2),
+        _ => chars
+            .take_while(|c| !c.is_whitespace() && !":=,]}".contains(*c))
+            .count(),
+    };
+    len.max(1)
+}
+
+/// Finds schema of the field by a path like `servers[0].host` (see [`KeyPath`])
+fn find_field<'a>(schema: &'a Value, path: &str) -> Option<&'a Value> {
+    let defs = &schema["$defs"];
+    let mut sch = schema;
+
+    for segment in KeyPath::parse(path).ok()?.segments() {
+        let obj = resolve(sch, defs);
+        sch = match segment {
+            Segment::Key(key) => obj["properties"]
+                .get(key)
+                .or_else(|| {
+                    // Fields of enum variants
+                    obj["oneOf"]
+                        .as_array()?
+                        .iter()
+                        .find_map(|v| resolve(v, defs)["properties"].get(key))
+                })
+                .or_else(|| obj.get("additionalProperties"))?,
+            Segment::Index(_) => obj.get("items")?,
+            Segment::Wildcard => obj.get("additionalProperties").or(obj.get("items"))?,
+        };
+    }
+
+    Some(sch)
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
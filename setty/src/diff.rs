@@ -0,0 +1,276 @@
+use crate::Value;
+use crate::path::{KeyPath, Segment};
+use crate::schema_helpers::{get_enum_tag_property_name, resolve};
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Compares two config values, e.g. returned by [`crate::Config::data()`].
+///
+/// Without a schema enums are compared as plain objects, use [`diff_with_schema()`] or
+/// [`crate::Config::diff()`] to report switches between enum variants.
+pub fn diff(old: &Value, new: &Value) -> Diff {
+    let mut differ = Differ {
+        changes: Vec::new(),
+        path: KeyPath::new(),
+        defs: &Value::Null,
+    };
+    differ.diff(old, new, None);
+    Diff {
+        changes: differ.changes,
+    }
+}
+
+/// Compares two config values using the schema to detect switches between variants of
+/// internally tagged enums
+#[cfg(feature = "derive-jsonschema")]
+pub fn diff_with_schema(schema: &crate::schema::Schema, old: &Value, new: &Value) -> Diff {
+    let schema = schema.as_value();
+    let mut differ = Differ {
+        changes: Vec::new(),
+        path: KeyPath::new(),
+        defs: schema.get("$defs").unwrap_or(&Value::Null),
+    };
+    differ.diff(old, new, Some(schema));
+    Diff {
+        changes: differ.changes,
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Changes between two configs in the order of keys, see [`diff()`].
+///
+/// `Display` renders a human-readable report, e.g.:
+/// ```text
+/// + database.pool_size: 10
+/// - server.host: "localhost"
+/// ~ server.port: 8080 -> 80
+/// ~ storage: variant Local -> S3 {"bucket":"b","kind":"S3"}
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diff {
+    pub changes: Vec<Change>,
+}
+
+impl Diff {
+    /// Returns `true` if values are equal
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Renders changes as [JSON Patch](https://datatracker.ietf.org/doc/html/rfc6902)
+    /// that turns the old value into the new one
+    pub fn to_json_patch(&self) -> Value {
+        self.changes
+            .iter()
+            .map(|c| match &c.kind {
+                ChangeKind::Added(value) => serde_json::json!({
+                    "op": "add",
+                    "path": c.pointer,
+                    "value": value,
+                }),
+                ChangeKind::Removed(_) => serde_json::json!({
+                    "op": "remove",
+                    "path": c.pointer,
+                }),
+                ChangeKind::Changed { new, .. } | ChangeKind::VariantChanged { new, .. } => {
+                    serde_json::json!({
+                        "op": "replace",
+                        "path": c.pointer,
+                        "value": new,
+                    })
+                }
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for Diff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, change) in self.changes.iter().enumerate() {
+            if i != 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Single change found by [`diff()`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    /// Path of the value, e.g. `servers[0].host` (see [`KeyPath`]), empty for the root
+    pub path: String,
+    /// Same path as [JSON Pointer](https://datatracker.ietf.org/doc/html/rfc6901),
+    /// e.g. `/servers/0/host`
+    pub pointer: String,
+    pub kind: ChangeKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeKind {
+    Added(Value),
+    Removed(Value),
+    Changed {
+        old: Value,
+        new: Value,
+    },
+    /// Enum switched to a different variant, values are compared as a whole
+    VariantChanged {
+        old_variant: String,
+        new_variant: String,
+        old: Value,
+        new: Value,
+    },
+}
+
+impl std::fmt::Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = if self.path.is_empty() {
+            "<root>"
+        } else {
+            &self.path
+        };
+        match &self.kind {
+            ChangeKind::Added(value) => write!(f, "+ {path}: {value}"),
+            ChangeKind::Removed(value) => write!(f, "- {path}: {value}"),
+            ChangeKind::Changed { old, new } => write!(f, "~ {path}: {old} -> {new}"),
+            ChangeKind::VariantChanged {
+                old_variant,
+                new_variant,
+                new,
+                ..
+            } => write!(f, "~ {path}: variant {old_variant} -> {new_variant} {new}"),
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+struct Differ<'a> {
+    changes: Vec<Change>,
+    path: KeyPath,
+    defs: &'a Value,
+}
+
+impl<'a> Differ<'a> {
+    fn diff(&mut self, old: &'a Value, new: &'a Value, sch: Option<&'a Value>) {
+        if old == new {
+            return;
+        }
+
+        let sch = sch.map(|s| resolve(s, self.defs));
+
+        match (old, new) {
+            (Value::Object(o), Value::Object(n)) => {
+                let sch = match sch.and_then(|s| self.variants(s, old, new)) {
+                    Some(Ok(vsch)) => Some(vsch),
+                    Some(Err((old_variant, new_variant))) => {
+                        return self.push(ChangeKind::VariantChanged {
+                            old_variant,
+                            new_variant,
+                            old: old.clone(),
+                            new: new.clone(),
+                        });
+                    }
+                    None => sch,
+                };
+
+                for (k, ov) in o {
+                    self.path.push(Segment::Key(k.clone()));
+                    match n.get(k) {
+                        Some(nv) => self.diff(ov, nv, sch.and_then(|s| property(s, k))),
+                        None => self.push(ChangeKind::Removed(ov.clone())),
+                    }
+                    self.path.pop();
+                }
+                for (k, nv) in n.iter().filter(|(k, _)| !o.contains_key(*k)) {
+                    self.path.push(Segment::Key(k.clone()));
+                    self.push(ChangeKind::Added(nv.clone()));
+                    self.path.pop();
+                }
+            }
+            (Value::Array(o), Value::Array(n)) => {
+                let items = sch.and_then(|s| s.get("items"));
+
+                for (i, (ov, nv)) in o.iter().zip(n).enumerate() {
+                    self.path.push(Segment::Index(i));
+                    self.diff(ov, nv, items);
+                    self.path.pop();
+                }
+                // Removed in reverse so the indices in JSON Patch stay valid
+                for i in (n.len()..o.len()).rev() {
+                    self.path.push(Segment::Index(i));
+                    self.push(ChangeKind::Removed(o[i].clone()));
+                    self.path.pop();
+                }
+                for (i, nv) in n.iter().enumerate().skip(o.len()) {
+                    self.path.push(Segment::Index(i));
+                    self.push(ChangeKind::Added(nv.clone()));
+                    self.path.pop();
+                }
+            }
+            _ => self.push(ChangeKind::Changed {
+                old: old.clone(),
+                new: new.clone(),
+            }),
+        }
+    }
+
+    fn push(&mut self, kind: ChangeKind) {
+        let mut pointer = String::new();
+
+        for segment in self.path.segments() {
+            match segment {
+                Segment::Key(k) => {
+                    pointer.push('/');
+                    pointer.push_str(&k.replace('~', "~0").replace('/', "~1"));
+                }
+                Segment::Index(i) => pointer.push_str(&format!("/{i}")),
+                Segment::Wildcard => unreachable!("diff paths have no wildcards"),
+            }
+        }
+
+        self.changes.push(Change {
+            path: self.path.to_string(),
+            pointer,
+            kind,
+        });
+    }
+
+    /// For internally tagged enums returns the schema of the variant if both values
+    /// have the same tag, or names of the variants otherwise
+    fn variants(
+        &self,
+        sch: &'a Value,
+        old: &Value,
+        new: &Value,
+    ) -> Option<Result<&'a Value, (String, String)>> {
+        let variants = sch.get("oneOf")?.as_array()?;
+        let tag_property = get_enum_tag_property_name(variants)?;
+
+        let old_tag = old.get(tag_property)?;
+        let new_tag = new.get(tag_property)?;
+
+        if old_tag != new_tag {
+            let name = |tag: &Value| tag.as_str().map_or_else(|| tag.to_string(), String::from);
+            return Some(Err((name(old_tag), name(new_tag))));
+        }
+
+        let vsch = variants
+            .iter()
+            .find(|v| v["properties"][tag_property]["const"] == *new_tag)?;
+        Some(Ok(resolve(vsch, self.defs)))
+    }
+}
+
+fn property<'a>(sch: &'a Value, name: &str) -> Option<&'a Value> {
+    sch.get("properties")
+        .and_then(|p| p.get(name))
+        .or_else(|| sch.get("additionalProperties").filter(|v| v.is_object()))
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
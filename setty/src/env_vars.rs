@@ -0,0 +1,175 @@
+#![cfg(feature = "derive-jsonschema")]
+
+use crate::Value;
+use crate::schema_helpers::resolve;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Describes an environment variable that sets a leaf config property when used
+/// with [`crate::source::Env`] source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvVar {
+    /// Full name of the variable, e.g. `APP__database__host`
+    pub name: String,
+
+    /// Dotted path of the property in config, e.g. `database.host`
+    pub path: String,
+
+    /// JSON Schema type of the value, e.g. `string`
+    pub typ: String,
+
+    /// Possible values of enum-like properties
+    pub values: Vec<Value>,
+
+    /// Default value if property provides one
+    pub default: Option<Value>,
+
+    /// Property description
+    pub description: Option<String>,
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Enumerates all leaf properties in the schema (including ones that are specific
+/// to different enum variants) and names the env vars that set them.
+pub(crate) fn schema_to_env_vars(schema: &Value, prefix: &str, separator: &str) -> Vec<EnvVar> {
+    let mut ctx = Ctx {
+        prefix,
+        separator,
+        defs: schema.get("$defs").unwrap_or(&NULL),
+        ret: Vec::new(),
+    };
+
+    collect_rec(&mut ctx, &mut Vec::new(), schema, None);
+
+    ctx.ret
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+static NULL: Value = Value::Null;
+
+struct Ctx<'a> {
+    prefix: &'a str,
+    separator: &'a str,
+    defs: &'a Value,
+    ret: Vec<EnvVar>,
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+fn collect_rec<'a>(
+    ctx: &mut Ctx<'a>,
+    path: &mut Vec<&'a str>,
+    sch: &'a Value,
+    default: Option<&'a Value>,
+) {
+    let sch = resolve(sch, ctx.defs);
+
+    if let Some(properties) = sch.get("properties").and_then(|v| v.as_object()) {
+        let required = sch.get("required").and_then(|v| v.as_array());
+
+        for (pname, psch) in properties {
+            let is_required = required.is_some_and(|r| r.iter().any(|v| v == pname.as_str()));
+
+            // Fall back to the default of the parent object, non-required
+            // properties that don't specify a default are `null`
+            let pdefault = psch
+                .get("default")
+                .or_else(|| default.and_then(|d| d.get(pname)))
+                .or(if is_required { None } else { Some(&NULL) });
+
+            path.push(pname);
+
+            if has_properties(psch, ctx.defs) {
+                collect_rec(ctx, path, psch, pdefault);
+            } else {
+                add_leaf(ctx, path, psch, pdefault);
+            }
+
+            path.pop();
+        }
+    } else if let Some(one_of) = sch.get("oneOf").and_then(|v| v.as_array()) {
+        for vsch in one_of {
+            collect_rec(ctx, path, vsch, default);
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+fn add_leaf(ctx: &mut Ctx<'_>, path: &[&str], sch: &Value, default: Option<&Value>) {
+    let resolved = resolve(sch, ctx.defs);
+
+    let description = sch
+        .get("description")
+        .or_else(|| resolved.get("description"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_string());
+
+    let mut values = Vec::new();
+    if let Some(c) = resolved.get("const") {
+        values.push(c.clone());
+    }
+    if let Some(e) = resolved.get("enum").and_then(|v| v.as_array()) {
+        values.extend(e.iter().cloned());
+    }
+    if let Some(one_of) = resolved.get("oneOf").and_then(|v| v.as_array()) {
+        values.extend(one_of.iter().filter_map(|v| v.get("const")).cloned());
+    }
+
+    let dotted = path.join(".");
+
+    // Same property (e.g. the enum tag) may appear in multiple enum variants
+    if let Some(existing) = ctx.ret.iter_mut().find(|v| v.path == dotted) {
+        for v in values {
+            if !existing.values.contains(&v) {
+                existing.values.push(v);
+            }
+        }
+        if existing.description.is_none() {
+            existing.description = description;
+        }
+        return;
+    }
+
+    ctx.ret.push(EnvVar {
+        name: format!("{}{}", ctx.prefix, path.join(ctx.separator)),
+        path: dotted,
+        typ: type_name(resolved),
+        values,
+        default: default.cloned(),
+        description,
+    });
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Whether schema describes a struct or a tagged enum with nested properties
+fn has_properties(sch: &Value, defs: &Value) -> bool {
+    let sch = resolve(sch, defs);
+
+    if sch.get("properties").is_some() {
+        return true;
+    }
+
+    sch.get("oneOf")
+        .and_then(|v| v.as_array())
+        .is_some_and(|variants| variants.iter().any(|v| has_properties(v, defs)))
+}
+
+fn type_name(sch: &Value) -> String {
+    match sch.get("type") {
+        Some(Value::String(t)) => t.clone(),
+        Some(Value::Array(t)) => t
+            .iter()
+            .filter_map(|t| t.as_str())
+            .find(|t| *t != "null")
+            .unwrap_or("null")
+            .to_string(),
+        _ if sch.get("oneOf").is_some() || sch.get("const").is_some() => "string".to_string(),
+        _ => "any".to_string(),
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
@@ -1,29 +1,386 @@
 /////////////////////////////////////////////////////////////////////////////////////////
 
+type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
 /// Error returned when reading a [`crate::source::Source`].
+///
+/// Errors of merged config not matching the config type are classified into
+/// [`ReadError::UnknownField`], [`ReadError::MissingField`], [`ReadError::InvalidType`],
+/// [`ReadError::InvalidValue`] and [`ReadError::UnknownVariant`], falling back to
+/// [`ReadError::Deserialize`] for custom errors of the field types.
+///
+/// When [`crate::Config::with_all_errors`] is enabled such errors are reported all at
+/// once as [`ReadError::Multiple`].
 #[derive(Debug, thiserror::Error)]
-#[error(transparent)]
 pub enum ReadError {
-    // TODO: Expand this to provide more error kinds
     /// Boxed deserialization error
-    Serde(Box<dyn std::error::Error + Send + Sync>),
+    #[error(transparent)]
+    Serde(BoxedError),
+
+    /// Source data is malformed, e.g. a YAML file has invalid syntax
+    #[error("Failed to parse {location}: {source}{}", location.snippet_suffix())]
+    Syntax {
+        location: Location,
+        source: BoxedError,
+    },
+
+    /// Key is not a part of the config type
+    #[error(
+        "Unknown field `{path}`{}",
+        fmt_location_then(location, fmt_unknown_field(suggestions, expected))
+    )]
+    UnknownField {
+        /// Dotted path of the unknown key, e.g. `database.hots`
+        path: String,
+        /// Fields expected in its place
+        expected: Vec<String>,
+        /// Paths of known fields that look similar to the unknown one, closest first
+        suggestions: Vec<String>,
+        location: Option<Box<Location>>,
+    },
+
+    /// Required key has no value
+    #[error("Missing required field `{path}`{}", fmt_location(location))]
+    MissingField {
+        path: String,
+        /// Location of the parent object, if it was set in some source
+        location: Option<Box<Location>>,
+    },
+
+    /// Value has a wrong type, e.g. a string instead of a number
+    #[error(
+        "Invalid type at `{path}`: expected {expected}, found {found}{}",
+        fmt_location(location)
+    )]
+    InvalidType {
+        path: String,
+        expected: String,
+        found: String,
+        location: Option<Box<Location>>,
+    },
+
+    /// Value has a right type but is not acceptable, e.g. a number is out of range
+    #[error(
+        "Invalid value at `{path}`: expected {expected}, found {found}{}",
+        fmt_location(location)
+    )]
+    InvalidValue {
+        path: String,
+        expected: String,
+        found: String,
+        location: Option<Box<Location>>,
+    },
+
+    /// Enum tag does not match any of the variants
+    #[error(
+        "Unknown variant `{variant}` at `{path}`, expected {}{}",
+        fmt_one_of(expected),
+        fmt_location(location)
+    )]
+    UnknownVariant {
+        path: String,
+        variant: String,
+        expected: Vec<String>,
+        location: Option<Box<Location>>,
+    },
+
+    /// Merged data does not match the config type for other reasons
+    #[error("{source} at `{path}`{}", fmt_location(location))]
+    Deserialize {
+        /// Dotted path of the offending value, e.g. `database.port` or `items[0]`
+        path: String,
+        /// Where the offending value was set, if it can be traced back to a source
+        location: Option<Box<Location>>,
+        source: BoxedError,
+    },
 
     /// Failed validation
     #[cfg(feature = "derive-validate")]
+    #[error(transparent)]
     Validation(#[from] validator::ValidationErrors),
 
+    /// Value failed one of the validation rules, e.g. `#[config(validate(min_length = 5))]`
+    #[cfg(feature = "derive-validate")]
+    #[error("Validation failed at `{path}`: {message}{}", fmt_location(location))]
+    FieldValidation {
+        path: String,
+        /// Code of the rule, e.g. `length`
+        code: String,
+        message: String,
+        location: Option<Box<Location>>,
+    },
+
+    /// Validation rules were not checked because the config can't be deserialized even
+    /// with the invalid values left out, e.g. when a required field is missing
+    #[cfg(feature = "derive-validate")]
+    #[error("Validation rules were not checked due to the errors above")]
+    ValidationSkipped,
+
+    /// All errors found in the config, see [`crate::Config::with_all_errors`]
+    #[error("{}", fmt_multiple(.0))]
+    Multiple(Vec<ReadError>),
+
+    /// Path passed to [`crate::config::Config`] methods or sources is malformed or can't be
+    /// applied to the data
+    #[error(transparent)]
+    Path(#[from] PathError),
+
+    /// Operation requires a crate feature that is not enabled
+    #[error("{operation} requires `setty/{feature}` feature")]
+    FeatureRequired {
+        operation: &'static str,
+        feature: &'static str,
+    },
+
+    /// Failed to set up watching of config files, see [`crate::Config::watch`]
+    #[cfg(feature = "watch")]
+    #[error("Failed to watch config files: {0}")]
+    Watch(#[from] notify::Error),
+
     /// IO error when reading from disk
+    #[error(transparent)]
     Io(#[from] std::io::Error),
 }
 
+impl ReadError {
+    /// Dotted path of the offending value, if error relates to a specific key
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            Self::UnknownField { path, .. }
+            | Self::MissingField { path, .. }
+            | Self::InvalidType { path, .. }
+            | Self::InvalidValue { path, .. }
+            | Self::UnknownVariant { path, .. }
+            | Self::Deserialize { path, .. } => Some(path),
+            #[cfg(feature = "derive-validate")]
+            Self::FieldValidation { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Where the error originated, if known
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            Self::Syntax { location, .. } => Some(location),
+            Self::UnknownField { location, .. }
+            | Self::MissingField { location, .. }
+            | Self::InvalidType { location, .. }
+            | Self::InvalidValue { location, .. }
+            | Self::UnknownVariant { location, .. }
+            | Self::Deserialize { location, .. } => location.as_deref(),
+            #[cfg(feature = "derive-validate")]
+            Self::FieldValidation { location, .. } => location.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+fn fmt_location(location: &Option<Box<Location>>) -> String {
+    fmt_location_then(location, String::new())
+}
+
+/// Formats location followed by the rest of the message, keeping the snippet last
+fn fmt_location_then(location: &Option<Box<Location>>, rest: String) -> String {
+    match location {
+        Some(location) => format!(" in {location}{rest}{}", location.snippet_suffix()),
+        None => rest,
+    }
+}
+
+fn fmt_unknown_field(suggestions: &[String], expected: &[String]) -> String {
+    if !suggestions.is_empty() {
+        format!(", did you mean {}?", fmt_one_of(suggestions))
+    } else if !expected.is_empty() {
+        format!(", expected {}", fmt_one_of(expected))
+    } else {
+        String::new()
+    }
+}
+
+fn fmt_multiple(errors: &[ReadError]) -> String {
+    let mut ret = match errors.len() {
+        1 => "Found 1 error in config:".to_string(),
+        n => format!("Found {n} errors in config:"),
+    };
+    for err in errors {
+        ret.push_str("\n- ");
+        ret.push_str(&err.to_string().replace('\n', "\n  "));
+    }
+    ret
+}
+
+/// Formats names like `a`, `a` or `b`, one of `a`, `b`, `c`
+fn fmt_one_of(names: &[String]) -> String {
+    let names: Vec<_> = names.iter().map(|n| format!("`{n}`")).collect();
+    match names.as_slice() {
+        [] => "nothing".to_string(),
+        [a] => a.clone(),
+        [a, b] => format!("{a} or {b}"),
+        names => format!("one of {}", names.join(", ")),
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Position in a [`crate::source::Source`] that caused an error
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// Name of the source, e.g. file path
+    pub source_name: String,
+    /// One-based line number
+    pub line: Option<usize>,
+    /// One-based column number (in characters)
+    pub col: Option<usize>,
+    /// Text of the offending line
+    pub line_text: Option<String>,
+}
+
+impl Location {
+    /// Location that points at the whole source
+    pub fn new(source_name: impl Into<String>) -> Self {
+        Self {
+            source_name: source_name.into(),
+            line: None,
+            col: None,
+            line_text: None,
+        }
+    }
+
+    /// Location of a one-based line and column in the source text
+    pub fn at(source_name: impl Into<String>, text: &str, line: usize, col: usize) -> Self {
+        Self {
+            source_name: source_name.into(),
+            line: Some(line),
+            col: Some(col),
+            line_text: line
+                .checked_sub(1)
+                .and_then(|i| text.lines().nth(i))
+                .map(str::to_string),
+        }
+    }
+
+    /// Location of a byte offset in the source text
+    pub fn at_offset(source_name: impl Into<String>, text: &str, offset: usize) -> Self {
+        let (line, col) = offset_to_position(text, offset);
+        Self::at(source_name, text, line, col)
+    }
+
+    /// Renders the offending line with a marker under the column, e.g.:
+    /// ```text
+    ///   |
+    /// 3 |   hots: localhost
+    ///   |   ^
+    /// ```
+    pub fn snippet(&self) -> Option<String> {
+        let line = self.line?;
+        let text = self.line_text.as_deref()?;
+
+        let num = line.to_string();
+        let pad = " ".repeat(num.len());
+
+        let mut ret = format!("{pad} |\n{num} | {text}");
+        if let Some(col) = self.col {
+            // Keep tabs to align the marker with the text
+            let prefix: String = text
+                .chars()
+                .take(col.saturating_sub(1))
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            ret.push_str(&format!("\n{pad} | {prefix}^"));
+        }
+        Some(ret)
+    }
+
+    fn snippet_suffix(&self) -> String {
+        self.snippet().map(|s| format!("\n{s}")).unwrap_or_default()
+    }
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source_name)?;
+        if let Some(line) = self.line {
+            write!(f, ":{line}")?;
+            if let Some(col) = self.col {
+                write!(f, ":{col}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Converts byte offset into one-based line and column
+pub(crate) fn offset_to_position(text: &str, offset: usize) -> (usize, usize) {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+
+    let before = &text[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let col = before[line_start..].chars().count() + 1;
+
+    (line, col)
+}
+
 /////////////////////////////////////////////////////////////////////////////////////////
 
 /// Error returned when saving configuration
 #[derive(Debug, thiserror::Error)]
-#[error(transparent)]
 pub enum WriteError {
+    #[error(transparent)]
     Read(#[from] ReadError),
+
+    /// Value can't be represented in the target format, e.g. `null` in TOML
+    #[error("Failed to serialize config: {0}")]
+    Serialize(BoxedError),
+
+    #[error(transparent)]
     Io(#[from] std::io::Error),
 }
 
 /////////////////////////////////////////////////////////////////////////////////////////
+
+/// Error of parsing a [`crate::path::KeyPath`] or applying it to the data
+#[derive(Debug, thiserror::Error)]
+pub enum PathError {
+    /// Path has invalid syntax, e.g. unterminated quotes
+    #[error("Invalid path `{path}` at position {position}: {reason}")]
+    Syntax {
+        path: String,
+        /// Byte offset of the offending character
+        position: usize,
+        reason: &'static str,
+    },
+
+    /// Value on the path has a type that the next segment can't be applied to, e.g.
+    /// an index into an object
+    #[error("Expected {expected} at `{path}`, found {found}")]
+    InvalidType {
+        path: String,
+        expected: &'static str,
+        found: &'static str,
+    },
+
+    /// Array index is past the end of the array
+    #[error("Index {index} is out of bounds at `{path}` of length {len}")]
+    IndexOutOfBounds {
+        path: String,
+        index: usize,
+        len: usize,
+    },
+
+    /// Wildcard of the path didn't match any values
+    #[error("Path `{path}` does not match any values")]
+    NoMatch { path: String },
+
+    /// Path can't be used in this context, e.g. wildcards in the sources that don't
+    /// see the data of other sources
+    #[error("Path `{path}` is not supported here: {reason}")]
+    Unsupported { path: String, reason: &'static str },
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
@@ -0,0 +1,244 @@
+use std::{
+    borrow::Cow,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::Value;
+use crate::format::{Conversion, Format};
+use crate::path::KeyPath;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Object-safe counterpart of [`Format`] that operates on [`Value`]s, allowing to
+/// choose the format at runtime (see [`FormatRegistry`]).
+///
+/// Use [`FormatAdapter`] to turn any [`Format`] into a [`DynFormat`].
+pub trait DynFormat: Send + Sync {
+    fn name(&self) -> Cow<'static, str>;
+
+    /// File extensions associated with the format, e.g. `["yaml", "yml"]`
+    fn extensions(&self) -> &'static [&'static str];
+
+    fn deserialize(&self, string: &str) -> Result<Value, BoxedError>;
+
+    /// See [`Format::conversion()`]
+    fn conversion(&self) -> Conversion {
+        Conversion::None
+    }
+
+    fn deserialize_documents(&self, string: &str) -> Result<Vec<Value>, BoxedError>;
+
+    /// Same as [`DynFormat::deserialize()`] for contents of the file at `path`, letting
+    /// formats resolve paths relative to the file (see [`crate::format::YamlWithTags`])
+    fn deserialize_file(&self, path: &Path, string: &str) -> Result<Value, BoxedError> {
+        let _ = path;
+        self.deserialize(string)
+    }
+
+    /// Same as [`DynFormat::deserialize_documents()`] for contents of the file at `path`
+    fn deserialize_file_documents(
+        &self,
+        path: &Path,
+        string: &str,
+    ) -> Result<Vec<Value>, BoxedError> {
+        let _ = path;
+        self.deserialize_documents(string)
+    }
+
+    /// Other files that contents of the file at `path` refer to, e.g. via `!include`
+    /// (see [`crate::source::Source::watch_paths()`]). Defaults to none.
+    fn file_dependencies(&self, path: &Path, string: &str) -> Vec<PathBuf> {
+        let _ = (path, string);
+        Vec::new()
+    }
+
+    fn serialize(&self, value: &Value) -> Result<String, BoxedError>;
+
+    /// See [`Format::error_position()`], expects an error returned by this format
+    fn error_position(
+        &self,
+        error: &(dyn std::error::Error + Send + Sync + 'static),
+        string: &str,
+    ) -> Option<(usize, usize)>;
+
+    /// See [`Format::key_position()`]
+    fn key_position(&self, string: &str, path: &KeyPath) -> Option<(usize, usize)> {
+        let _ = (string, path);
+        None
+    }
+
+    /// See [`Format::update()`]
+    fn update(&self, original: &str, value: &Value) -> Result<String, BoxedError>;
+}
+
+impl std::fmt::Debug for dyn DynFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("DynFormat").field(&self.name()).finish()
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Implements [`DynFormat`] for a [`Format`]
+pub struct FormatAdapter<Fmt>(PhantomData<fn() -> Fmt>);
+
+impl<Fmt: Format> FormatAdapter<Fmt> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<Fmt: Format> Default for FormatAdapter<Fmt> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Fmt: Format> DynFormat for FormatAdapter<Fmt> {
+    fn name(&self) -> Cow<'static, str> {
+        Fmt::name()
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        Fmt::extensions()
+    }
+
+    fn deserialize(&self, string: &str) -> Result<Value, BoxedError> {
+        Ok(Fmt::deserialize(string)?)
+    }
+
+    fn conversion(&self) -> Conversion {
+        Fmt::conversion()
+    }
+
+    fn deserialize_documents(&self, string: &str) -> Result<Vec<Value>, BoxedError> {
+        Ok(Fmt::deserialize_documents(string)?)
+    }
+
+    fn serialize(&self, value: &Value) -> Result<String, BoxedError> {
+        Ok(Fmt::serialize(value)?)
+    }
+
+    fn error_position(
+        &self,
+        error: &(dyn std::error::Error + Send + Sync + 'static),
+        string: &str,
+    ) -> Option<(usize, usize)> {
+        Fmt::error_position(error.downcast_ref::<Fmt::ErrorDe>()?, string)
+    }
+
+    fn key_position(&self, string: &str, path: &KeyPath) -> Option<(usize, usize)> {
+        Fmt::key_position(string, path)
+    }
+
+    fn update(&self, original: &str, value: &Value) -> Result<String, BoxedError> {
+        Ok(Fmt::update(original, value)?)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Set of formats that can be looked up by name or file extension at runtime,
+/// e.g. to handle `--format yaml` argument.
+///
+/// Lookups are case-insensitive, formats added later take precedence.
+#[derive(Clone, Default)]
+pub struct FormatRegistry {
+    formats: Vec<Arc<dyn DynFormat>>,
+}
+
+impl FormatRegistry {
+    /// Creates an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry with all formats enabled via crate features
+    pub fn with_defaults() -> Self {
+        #[allow(unused_mut)]
+        let mut reg = Self::new();
+
+        #[cfg(feature = "fmt-json")]
+        {
+            reg = reg.with_format::<crate::format::JsonPretty>();
+        }
+        #[cfg(feature = "fmt-json5")]
+        {
+            reg = reg.with_format::<crate::format::Json5>();
+        }
+        #[cfg(feature = "fmt-yaml")]
+        {
+            reg = reg.with_format::<crate::format::Yaml>();
+        }
+        #[cfg(feature = "fmt-toml")]
+        {
+            reg = reg.with_format::<crate::format::Toml>();
+        }
+        #[cfg(feature = "fmt-ini")]
+        {
+            reg = reg.with_format::<crate::format::Ini>();
+        }
+        #[cfg(feature = "fmt-properties")]
+        {
+            reg = reg.with_format::<crate::format::Properties>();
+        }
+        #[cfg(feature = "fmt-ron")]
+        {
+            reg = reg.with_format::<crate::format::Ron>();
+        }
+        #[cfg(feature = "fmt-hcl")]
+        {
+            reg = reg.with_format::<crate::format::Hcl>();
+        }
+
+        reg
+    }
+
+    /// Adds a statically typed format
+    pub fn with_format<Fmt: Format + 'static>(self) -> Self {
+        let format: Arc<dyn DynFormat> = Arc::new(FormatAdapter::<Fmt>::new());
+        self.with_dyn_format(format)
+    }
+
+    /// Adds a dynamic format
+    pub fn with_dyn_format(mut self, format: impl Into<Arc<dyn DynFormat>>) -> Self {
+        self.formats.push(format.into());
+        self
+    }
+
+    /// Returns format with specified name
+    pub fn by_name(&self, name: &str) -> Option<Arc<dyn DynFormat>> {
+        self.formats
+            .iter()
+            .rev()
+            .find(|f| f.name().eq_ignore_ascii_case(name))
+            .cloned()
+    }
+
+    /// Returns format associated with specified file extension (without the leading dot)
+    pub fn by_extension(&self, ext: &str) -> Option<Arc<dyn DynFormat>> {
+        self.formats
+            .iter()
+            .rev()
+            .find(|f| f.extensions().iter().any(|e| e.eq_ignore_ascii_case(ext)))
+            .cloned()
+    }
+
+    /// Returns format of a file based on its extension
+    pub fn for_path(&self, path: impl AsRef<Path>) -> Option<Arc<dyn DynFormat>> {
+        self.by_extension(path.as_ref().extension()?.to_str()?)
+    }
+
+    /// Iterates over registered formats
+    pub fn formats(&self) -> impl Iterator<Item = &Arc<dyn DynFormat>> {
+        self.formats.iter()
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
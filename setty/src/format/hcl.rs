@@ -0,0 +1,110 @@
+#![cfg(feature = "fmt-hcl")]
+
+use crate::Value;
+use crate::format::Format;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// [HCL](https://github.com/hashicorp/hcl) format using `hcl-rs` crate.
+///
+/// Blocks are mapped to nested objects the same way as in Terraform's JSON syntax:
+/// - `database { host = "x" }` becomes `{"database": {"host": "x"}}`
+/// - block labels add nesting, so `server "a" { port = 1 }` becomes
+///   `{"server": {"a": {"port": 1}}}`
+/// - repeated blocks with the same name become an array of objects
+///
+/// Note that a single block is always read as an object, so a list with one
+/// element has to be written as an attribute, e.g. `plugins = [{ name = "x" }]`.
+///
+/// Expressions like `1 + 2` and templates are evaluated when reading, but there
+/// are no variables or functions in scope.
+///
+/// When serializing, objects are written as blocks (if their keys are valid
+/// identifiers) and all other values as attributes.
+pub struct Hcl;
+
+impl Format for Hcl {
+    type ErrorDe = HclError;
+    type ErrorSer = HclError;
+
+    fn name() -> std::borrow::Cow<'static, str> {
+        "hcl".into()
+    }
+
+    fn extensions() -> &'static [&'static str] {
+        &["hcl"]
+    }
+
+    fn deserialize<T: serde::de::DeserializeOwned>(string: &str) -> Result<T, Self::ErrorDe> {
+        Ok(hcl::eval::from_str(string, &hcl::eval::Context::new())?)
+    }
+
+    fn error_position(error: &Self::ErrorDe, _string: &str) -> Option<(usize, usize)> {
+        match error {
+            HclError::Hcl(hcl::Error::Parse(e)) => {
+                Some((e.location().line(), e.location().column()))
+            }
+            _ => None,
+        }
+    }
+
+    fn serialize<T: serde::ser::Serialize>(value: &T) -> Result<String, Self::ErrorSer> {
+        let Value::Object(root) = serde_json::to_value(value)? else {
+            return Err(HclError::Unsupported(
+                "Only objects can be represented in HCL format".to_string(),
+            ));
+        };
+
+        if let Some(key) = root.keys().find(|k| !is_ident(k)) {
+            return Err(HclError::Unsupported(format!(
+                "Top-level key `{key}` is not a valid HCL identifier"
+            )));
+        }
+
+        let mut body = hcl::Body::builder();
+        for (key, value) in root {
+            body = body.add_structure(to_structure(key, value)?);
+        }
+
+        Ok(hcl::to_string(&body.build())?)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Error returned by [`Hcl`] format
+#[derive(Debug, thiserror::Error)]
+pub enum HclError {
+    #[error(transparent)]
+    Hcl(#[from] hcl::Error),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error("{0}")]
+    Unsupported(String),
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+fn to_structure(key: String, value: Value) -> Result<hcl::Structure, HclError> {
+    match value {
+        Value::Object(obj) if obj.keys().all(|k| is_ident(k)) => {
+            let mut block = hcl::Block::builder(hcl::Identifier::new(key)?);
+            for (key, value) in obj {
+                block = block.add_structure(to_structure(key, value)?);
+            }
+            Ok(block.build().into())
+        }
+        value => {
+            let value: hcl::Value = serde_json::from_value(value)?;
+            Ok(hcl::Attribute::new(hcl::Identifier::new(key)?, value).into())
+        }
+    }
+}
+
+fn is_ident(s: &str) -> bool {
+    hcl::Identifier::new(s).is_ok()
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
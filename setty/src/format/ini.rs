@@ -0,0 +1,218 @@
+#![cfg(feature = "fmt-ini")]
+
+use std::fmt::Write;
+
+use crate::Value;
+use crate::format::{Conversion, Format};
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// INI format using `rust-ini` crate.
+///
+/// Sections are mapped to nested objects by splitting their names with `SEP`
+/// separator, so `[database.pool]` becomes `{"database": {"pool": {...}}}`.
+/// Keys that appear before the first section are placed at the top level.
+///
+/// INI has no types, so all values are read as strings (see [`Conversion::Types`])
+/// that are converted to the types of config fields, e.g. `port = 5432` for a number or
+/// `hosts = ["a", "b"]` for an array. Quotes are optional and are only needed to keep
+/// leading or trailing whitespace, e.g. `prefix = " > "`.
+///
+/// Serialization writes numbers and booleans as is and arrays as JSON.
+///
+/// Use [`IniWithSeparator`] to split section names with a different separator.
+pub type Ini = IniWithSeparator<'.'>;
+
+/// Same as [`Ini`] but splits section names into nested objects using `SEP`
+/// separator, e.g. `IniWithSeparator<'/'>` for `[database/pool]`.
+pub struct IniWithSeparator<const SEP: char>;
+
+impl<const SEP: char> Format for IniWithSeparator<SEP> {
+    type ErrorDe = IniError;
+    type ErrorSer = IniError;
+
+    fn name() -> std::borrow::Cow<'static, str> {
+        "ini".into()
+    }
+
+    fn extensions() -> &'static [&'static str] {
+        &["ini"]
+    }
+
+    fn conversion() -> Conversion {
+        Conversion::Types
+    }
+
+    fn deserialize<T: serde::de::DeserializeOwned>(string: &str) -> Result<T, Self::ErrorDe> {
+        let opts = ini::ParseOption {
+            // Handling quotes ourselves to only strip the ones around the whole value
+            enabled_quote: false,
+            ..Default::default()
+        };
+
+        let doc = ini::Ini::load_from_str_opt(string, opts)?;
+
+        let mut root = serde_json::Map::new();
+
+        for (section, props) in &doc {
+            let mut obj = &mut root;
+
+            if let Some(section) = section {
+                for seg in section.split(SEP) {
+                    let entry = obj
+                        .entry(seg.to_string())
+                        .or_insert_with(|| Value::Object(Default::default()));
+
+                    let Value::Object(child) = entry else {
+                        return Err(IniError::Unsupported(format!(
+                            "Section `{section}` conflicts with a value of key `{seg}`"
+                        )));
+                    };
+                    obj = child;
+                }
+            }
+
+            for (key, value) in props {
+                obj.insert(key.to_string(), Value::String(unquote(value).to_string()));
+            }
+        }
+
+        Ok(serde_json::from_value(Value::Object(root))?)
+    }
+
+    fn error_position(error: &Self::ErrorDe, _string: &str) -> Option<(usize, usize)> {
+        match error {
+            IniError::Parse(e) => Some((e.line, e.col)),
+            _ => None,
+        }
+    }
+
+    fn serialize<T: serde::ser::Serialize>(value: &T) -> Result<String, Self::ErrorSer> {
+        let Value::Object(root) = serde_json::to_value(value)? else {
+            return Err(IniError::Unsupported(
+                "Only objects can be represented in INI format".to_string(),
+            ));
+        };
+
+        let mut buf = String::new();
+
+        // General section goes first
+        write_props(&mut buf, &root)?;
+
+        for (key, value) in &root {
+            if let Value::Object(obj) = value {
+                write_section::<SEP>(&mut buf, key, obj)?;
+            }
+        }
+
+        Ok(buf)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Error returned by [`Ini`] format
+#[derive(Debug, thiserror::Error)]
+pub enum IniError {
+    #[error(transparent)]
+    Parse(#[from] ini::ParseError),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error("{0}")]
+    Unsupported(String),
+}
+
+impl From<std::fmt::Error> for IniError {
+    fn from(value: std::fmt::Error) -> Self {
+        Self::Unsupported(value.to_string())
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+fn write_section<const SEP: char>(
+    buf: &mut String,
+    name: &str,
+    obj: &serde_json::Map<String, Value>,
+) -> Result<(), IniError> {
+    let has_props = obj.values().any(|v| !v.is_object());
+
+    // Writing a header for empty objects too to preserve them
+    if has_props || obj.is_empty() {
+        if !buf.is_empty() {
+            writeln!(buf)?;
+        }
+        writeln!(buf, "[{name}]")?;
+        write_props(buf, obj)?;
+    }
+
+    for (key, value) in obj {
+        if let Value::Object(child) = value {
+            write_section::<SEP>(buf, &format!("{name}{SEP}{key}"), child)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_props(buf: &mut String, obj: &serde_json::Map<String, Value>) -> Result<(), IniError> {
+    for (key, value) in obj {
+        let value = match value {
+            // Absent values are the same as nulls
+            Value::Null | Value::Object(_) => continue,
+            Value::Bool(v) => v.to_string(),
+            Value::Number(v) => v.to_string(),
+            Value::Array(_) => escape(&serde_json::to_string(value)?),
+            Value::String(v) => {
+                if needs_quotes(v) {
+                    format!("\"{}\"", escape(v))
+                } else {
+                    escape(v)
+                }
+            }
+        };
+
+        writeln!(
+            buf,
+            "{} = {value}",
+            escape(key).replace('=', "\\=").replace(':', "\\:")
+        )?;
+    }
+    Ok(())
+}
+
+fn unquote(s: &str) -> &str {
+    for q in ['"', '\''] {
+        if s.len() >= 2 && s.starts_with(q) && s.ends_with(q) {
+            return &s[1..s.len() - 1];
+        }
+    }
+    s
+}
+
+/// Whether string has to be quoted to be read back as is
+fn needs_quotes(s: &str) -> bool {
+    s.trim() != s || unquote(s) != s
+}
+
+fn escape(s: &str) -> String {
+    let mut ret = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => ret.push_str("\\\\"),
+            '\n' => ret.push_str("\\n"),
+            '\r' => ret.push_str("\\r"),
+            '\t' => ret.push_str("\\t"),
+            '\0' => ret.push_str("\\0"),
+            c if c.is_control() => {
+                let _ = write!(ret, "\\x{:04x}", c as u32);
+            }
+            c => ret.push(c),
+        }
+    }
+    ret
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
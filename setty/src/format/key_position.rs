@@ -0,0 +1,185 @@
+#![cfg(any(feature = "fmt-json", feature = "fmt-yaml", feature = "fmt-toml"))]
+
+use crate::path::{KeyPath, Segment};
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Segments up to the last key, as elements of arrays are located by the key of
+/// the array
+fn key_segments(path: &KeyPath) -> Option<&[Segment]> {
+    let segments = path.segments();
+    let last_key = segments
+        .iter()
+        .rposition(|s| matches!(s, Segment::Key(_)))?;
+    Some(&segments[..=last_key])
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Uses `toml_edit` spans of the keys
+#[cfg(feature = "fmt-toml")]
+pub(super) fn toml(string: &str, path: &KeyPath) -> Option<usize> {
+    use toml_edit::{Item, Table, TableLike, Value};
+
+    enum Node<'a> {
+        Item(&'a Item),
+        Table(&'a Table),
+        Value(&'a Value),
+    }
+
+    impl<'a> Node<'a> {
+        fn table(&self) -> Option<&'a dyn TableLike> {
+            match self {
+                Node::Item(item) => item.as_table_like(),
+                Node::Table(table) => Some(*table),
+                Node::Value(value) => value.as_inline_table().map(|t| t as &dyn TableLike),
+            }
+        }
+
+        fn element(&self, index: usize) -> Option<Node<'a>> {
+            match self {
+                Node::Item(Item::ArrayOfTables(tables)) => tables.get(index).map(Node::Table),
+                Node::Item(item) => item.as_array()?.get(index).map(Node::Value),
+                Node::Table(_) => None,
+                Node::Value(value) => value.as_array()?.get(index).map(Node::Value),
+            }
+        }
+    }
+
+    let doc = toml_edit::Document::parse(string).ok()?;
+    let mut node = Node::Table(doc.as_table());
+    let mut span = None;
+
+    for segment in key_segments(path)? {
+        node = match segment {
+            Segment::Key(key) => {
+                let (key, item) = node.table()?.get_key_value(key)?;
+                span = key.span();
+                Node::Item(item)
+            }
+            Segment::Index(index) => node.element(*index)?,
+            Segment::Wildcard => return None,
+        };
+    }
+
+    Some(span?.start)
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Deserializes the document with a seed that fails at the key, so that the error
+/// carries the position the deserializer attaches to errors of the current token
+#[cfg(any(feature = "fmt-json", feature = "fmt-yaml"))]
+pub(super) fn find_key<'de, D>(de: D, path: &KeyPath) -> Option<D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::DeserializeSeed as _;
+
+    let err = Finder(key_segments(path)?).deserialize(de).err()?;
+    err.to_string().contains(FOUND).then_some(err)
+}
+
+#[cfg(any(feature = "fmt-json", feature = "fmt-yaml"))]
+const FOUND: &str = "\0setty: key found";
+
+#[cfg(any(feature = "fmt-json", feature = "fmt-yaml"))]
+struct Finder<'a>(&'a [Segment]);
+
+#[cfg(any(feature = "fmt-json", feature = "fmt-yaml"))]
+impl<'de> serde::de::DeserializeSeed<'de> for Finder<'_> {
+    type Value = ();
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, de: D) -> Result<(), D::Error> {
+        de.deserialize_any(self)
+    }
+}
+
+#[cfg(any(feature = "fmt-json", feature = "fmt-yaml"))]
+impl<'de> serde::de::Visitor<'de> for Finder<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("a map or a sequence")
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let Some((Segment::Key(key), rest)) = self.0.split_first() else {
+            return Ok(());
+        };
+
+        let seed = KeySeed {
+            key,
+            last: rest.is_empty(),
+        };
+        while let Some(matched) = map.next_key_seed(seed)? {
+            if matched {
+                return map.next_value_seed(Finder(rest));
+            }
+            map.next_value::<serde::de::IgnoredAny>()?;
+        }
+        Ok(())
+    }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let Some((Segment::Index(index), rest)) = self.0.split_first() else {
+            return Ok(());
+        };
+
+        for _ in 0..*index {
+            if seq.next_element::<serde::de::IgnoredAny>()?.is_none() {
+                return Ok(());
+            }
+        }
+        seq.next_element_seed(Finder(rest))?;
+        Ok(())
+    }
+}
+
+/// Compares the key with the one being searched, failing with [`FOUND`] at the last
+/// key of the path
+#[cfg(any(feature = "fmt-json", feature = "fmt-yaml"))]
+#[derive(Clone, Copy)]
+struct KeySeed<'a> {
+    key: &'a str,
+    last: bool,
+}
+
+#[cfg(any(feature = "fmt-json", feature = "fmt-yaml"))]
+impl<'de> serde::de::DeserializeSeed<'de> for KeySeed<'_> {
+    type Value = bool;
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, de: D) -> Result<bool, D::Error> {
+        de.deserialize_any(self)
+    }
+}
+
+#[cfg(any(feature = "fmt-json", feature = "fmt-yaml"))]
+impl<'de> serde::de::Visitor<'de> for KeySeed<'_> {
+    type Value = bool;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("a key")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<bool, E> {
+        match v == self.key {
+            true if self.last => Err(E::custom(FOUND)),
+            matched => Ok(matched),
+        }
+    }
+
+    fn visit_bool<E: serde::de::Error>(self, v: bool) -> Result<bool, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<bool, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<bool, E> {
+        self.visit_str(&v.to_string())
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
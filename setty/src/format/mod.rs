@@ -0,0 +1,372 @@
+pub mod dyn_format;
+pub mod hcl;
+pub mod ini;
+mod key_position;
+pub mod properties;
+pub mod ron;
+#[cfg(feature = "fmt-toml")]
+mod toml_update;
+mod yaml_update;
+mod yaml_with_tags;
+
+pub use dyn_format::*;
+#[cfg(feature = "fmt-hcl")]
+pub use hcl::*;
+#[cfg(feature = "fmt-ini")]
+pub use ini::*;
+#[cfg(feature = "fmt-properties")]
+pub use properties::*;
+#[cfg(feature = "fmt-ron")]
+pub use ron::*;
+#[cfg(feature = "fmt-yaml")]
+pub use yaml_with_tags::*;
+
+use crate::path::KeyPath;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Trait for serialization/deserialization formats used by [`crate::source::Source`]s
+/// and when writing config files.
+pub trait Format {
+    type ErrorDe: std::error::Error + Send + Sync + 'static;
+    type ErrorSer: std::error::Error + Send + Sync + 'static;
+
+    fn name() -> std::borrow::Cow<'static, str>;
+
+    /// File extensions associated with the format, e.g. `["yaml", "yml"]`
+    fn extensions() -> &'static [&'static str] {
+        &[]
+    }
+
+    fn deserialize<T: serde::de::DeserializeOwned>(string: &str) -> Result<T, Self::ErrorDe>;
+
+    /// How values read by the format have to be converted to match the config types,
+    /// see [`Conversion`]
+    fn conversion() -> Conversion {
+        Conversion::None
+    }
+
+    /// Deserializes all documents in the string for formats that allow several
+    /// of them per file (e.g. YAML's `---` separated documents).
+    ///
+    /// Returns a single document by default.
+    fn deserialize_documents<T: serde::de::DeserializeOwned>(
+        string: &str,
+    ) -> Result<Vec<T>, Self::ErrorDe> {
+        Ok(vec![Self::deserialize(string)?])
+    }
+
+    fn serialize<T: serde::ser::Serialize>(value: &T) -> Result<String, Self::ErrorSer>;
+
+    /// Returns one-based line and column in the `string` where deserialization failed,
+    /// if the error carries this information.
+    fn error_position(error: &Self::ErrorDe, string: &str) -> Option<(usize, usize)> {
+        let _ = (error, string);
+        None
+    }
+
+    /// Returns one-based line and column of the key under `path` in the `string` using
+    /// positions reported by the parser, used to point at the offending key when the
+    /// merged config fails to deserialize. Elements of arrays are located by the key of
+    /// the array, and in strings with several documents the last document that has
+    /// the key is used.
+    ///
+    /// Returns `None` by default, in which case a best-effort text search is used.
+    fn key_position(string: &str, path: &KeyPath) -> Option<(usize, usize)> {
+        let _ = (string, path);
+        None
+    }
+
+    /// Serializes the value in place of the original document, preserving comments,
+    /// order and formatting of the unchanged parts where format supports it.
+    ///
+    /// Re-serializes the whole value by default.
+    fn update(original: &str, value: &crate::Value) -> Result<String, Self::ErrorSer> {
+        let _ = original;
+        Self::serialize(value)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Conversion of values read by a [`Format`] that don't map onto config types directly.
+///
+/// Performed by [`crate::config::Config`] using the config schema before merging the
+/// values with other sources (requires `derive-jsonschema` feature).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Conversion {
+    /// Values already have the types of the config
+    #[default]
+    None,
+    /// Format has no types (e.g. INI) and reads all scalars as strings that are converted
+    /// to the types of the fields, e.g. `"5432"` to a number
+    Types,
+    /// Format names structs and enum variants (e.g. RON) and reads them in serde's
+    /// externally tagged representation, e.g. `{"Postgres": {...}}`, that is mapped onto
+    /// internally tagged enums, while names of structs are dropped
+    Names,
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// JSON format using `serde_json` crate.
+#[cfg(feature = "fmt-json")]
+pub struct Json;
+
+#[cfg(feature = "fmt-json")]
+impl Format for Json {
+    type ErrorDe = serde_json::Error;
+    type ErrorSer = serde_json::Error;
+
+    fn name() -> std::borrow::Cow<'static, str> {
+        "json".into()
+    }
+
+    fn extensions() -> &'static [&'static str] {
+        &["json"]
+    }
+
+    fn deserialize<T: serde::de::DeserializeOwned>(string: &str) -> Result<T, Self::ErrorDe> {
+        serde_json::from_str(string)
+    }
+
+    fn error_position(error: &Self::ErrorDe, _string: &str) -> Option<(usize, usize)> {
+        (error.line() != 0).then(|| (error.line(), error.column()))
+    }
+
+    fn key_position(string: &str, path: &KeyPath) -> Option<(usize, usize)> {
+        json_key_position(string, path)
+    }
+
+    fn serialize<T: serde::ser::Serialize>(value: &T) -> Result<String, Self::ErrorSer> {
+        serde_json::to_string(value)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Same as [`Json`] but uses pretty-printing when serializing.
+#[cfg(feature = "fmt-json")]
+pub struct JsonPretty;
+
+#[cfg(feature = "fmt-json")]
+impl Format for JsonPretty {
+    type ErrorDe = serde_json::Error;
+    type ErrorSer = serde_json::Error;
+
+    fn name() -> std::borrow::Cow<'static, str> {
+        "json".into()
+    }
+
+    fn extensions() -> &'static [&'static str] {
+        &["json"]
+    }
+
+    fn deserialize<T: serde::de::DeserializeOwned>(string: &str) -> Result<T, Self::ErrorDe> {
+        serde_json::from_str(string)
+    }
+
+    fn error_position(error: &Self::ErrorDe, _string: &str) -> Option<(usize, usize)> {
+        (error.line() != 0).then(|| (error.line(), error.column()))
+    }
+
+    fn key_position(string: &str, path: &KeyPath) -> Option<(usize, usize)> {
+        json_key_position(string, path)
+    }
+
+    fn serialize<T: serde::ser::Serialize>(value: &T) -> Result<String, Self::ErrorSer> {
+        serde_json::to_string_pretty(value)
+    }
+}
+
+/// Errors are positioned after the closing quote of the key, so the position is moved
+/// to the opening one
+#[cfg(feature = "fmt-json")]
+fn json_key_position(string: &str, path: &KeyPath) -> Option<(usize, usize)> {
+    let mut de = serde_json::Deserializer::from_str(string);
+    let err = key_position::find_key(&mut de, path)?;
+
+    let line_start = string
+        .split_inclusive('\n')
+        .take(err.line() - 1)
+        .map(str::len)
+        .sum();
+    let before = string[line_start..]
+        .chars()
+        .take(err.column())
+        .collect::<String>();
+    let quote = before.strip_suffix('"')?.rfind('"')?;
+    let col = before[..quote].chars().count() + 1;
+
+    Some((err.line(), col))
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// JSON5 format using `json5` crate.
+///
+/// Being a superset of JSON that allows comments, trailing commas, unquoted keys
+/// etc. it can also be used to read JSONC files. Values are serialized as pretty-printed
+/// JSON, which is valid JSON5.
+#[cfg(feature = "fmt-json5")]
+pub struct Json5;
+
+#[cfg(feature = "fmt-json5")]
+impl Format for Json5 {
+    type ErrorDe = json5::Error;
+    type ErrorSer = serde_json::Error;
+
+    fn name() -> std::borrow::Cow<'static, str> {
+        "json5".into()
+    }
+
+    fn extensions() -> &'static [&'static str] {
+        &["json5", "jsonc"]
+    }
+
+    fn deserialize<T: serde::de::DeserializeOwned>(string: &str) -> Result<T, Self::ErrorDe> {
+        json5::from_str(string)
+    }
+
+    fn error_position(error: &Self::ErrorDe, _string: &str) -> Option<(usize, usize)> {
+        let json5::Error::Message { location, .. } = error;
+        location.as_ref().map(|l| (l.line, l.column))
+    }
+
+    fn serialize<T: serde::ser::Serialize>(value: &T) -> Result<String, Self::ErrorSer> {
+        serde_json::to_string_pretty(value)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// YAML format using `serde_yaml_ng` crate.
+///
+/// Files with multiple `---` separated documents are loaded by [`crate::source::File`]
+/// as several layers that are merged in order.
+///
+/// Updating existing block-style documents only touches the changed keys and sequence
+/// items, preserving comments, anchors and key order. Documents that can't be patched
+/// this way are refused rather than re-serialized.
+#[cfg(feature = "fmt-yaml")]
+pub struct Yaml;
+
+#[cfg(feature = "fmt-yaml")]
+impl Format for Yaml {
+    type ErrorDe = serde_yaml::Error;
+    type ErrorSer = serde_yaml::Error;
+
+    fn name() -> std::borrow::Cow<'static, str> {
+        "yaml".into()
+    }
+
+    fn extensions() -> &'static [&'static str] {
+        &["yaml", "yml"]
+    }
+
+    fn deserialize<T: serde::de::DeserializeOwned>(string: &str) -> Result<T, Self::ErrorDe> {
+        serde_yaml::from_str(string)
+    }
+
+    fn deserialize_documents<T: serde::de::DeserializeOwned>(
+        string: &str,
+    ) -> Result<Vec<T>, Self::ErrorDe> {
+        serde_yaml::Deserializer::from_str(string)
+            .map(T::deserialize)
+            .collect()
+    }
+
+    fn error_position(error: &Self::ErrorDe, _string: &str) -> Option<(usize, usize)> {
+        error.location().map(|l| (l.line(), l.column()))
+    }
+
+    fn key_position(string: &str, path: &KeyPath) -> Option<(usize, usize)> {
+        serde_yaml::Deserializer::from_str(string)
+            .filter_map(|doc| key_position::find_key(doc, path)?.location())
+            .map(|l| (l.line(), l.column()))
+            .last()
+    }
+
+    #[cfg(not(feature = "fmt-yaml-arbitrary-precision-hack"))]
+    fn serialize<T: serde::ser::Serialize>(value: &T) -> Result<String, Self::ErrorSer> {
+        serde_yaml::to_string(value)
+    }
+
+    #[cfg(feature = "fmt-yaml-arbitrary-precision-hack")]
+    fn serialize<T: serde::ser::Serialize>(value: &T) -> Result<String, Self::ErrorSer> {
+        // To avoid outputting `$serde_json::private::Number` tag we resort to ugly transcoding
+        // See: https://github.com/acatton/serde-yaml-ng/issues/31
+        use serde::ser::Error as _;
+
+        let s = serde_json::to_string(value).map_err(serde_yaml::Error::custom)?;
+        let value: serde_yaml::Value = serde_yaml::from_str(&s)?;
+        serde_yaml::to_string(&value)
+    }
+
+    fn update(original: &str, value: &crate::Value) -> Result<String, Self::ErrorSer> {
+        use serde::ser::Error as _;
+
+        // Re-serializing would silently drop comments and reorder keys
+        let old: crate::Value = Self::deserialize(original)?;
+        yaml_update::update(original, &old, value).ok_or_else(|| {
+            serde_yaml::Error::custom(
+                "YAML document can't be updated without losing its comments and formatting, \
+                 e.g. because it has flow-style root or multiple documents",
+            )
+        })
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// TOML format using the `toml` crate.
+///
+/// Updating existing documents is done via `toml_edit` crate and only touches
+/// the changed keys, preserving comments and formatting.
+#[cfg(feature = "fmt-toml")]
+pub struct Toml;
+
+#[cfg(feature = "fmt-toml")]
+impl Format for Toml {
+    type ErrorDe = toml::de::Error;
+    type ErrorSer = toml::ser::Error;
+
+    fn name() -> std::borrow::Cow<'static, str> {
+        "toml".into()
+    }
+
+    fn extensions() -> &'static [&'static str] {
+        &["toml"]
+    }
+
+    fn deserialize<T: serde::de::DeserializeOwned>(string: &str) -> Result<T, Self::ErrorDe> {
+        toml::from_str(string)
+    }
+
+    fn error_position(error: &Self::ErrorDe, string: &str) -> Option<(usize, usize)> {
+        Some(crate::errors::offset_to_position(
+            string,
+            error.span()?.start,
+        ))
+    }
+
+    fn key_position(string: &str, path: &KeyPath) -> Option<(usize, usize)> {
+        Some(crate::errors::offset_to_position(
+            string,
+            key_position::toml(string, path)?,
+        ))
+    }
+
+    fn serialize<T: serde::ser::Serialize>(value: &T) -> Result<String, Self::ErrorSer> {
+        toml::to_string(value)
+    }
+
+    fn update(original: &str, value: &crate::Value) -> Result<String, Self::ErrorSer> {
+        use serde::ser::Error as _;
+
+        let old: crate::Value = toml::from_str(original).map_err(toml::ser::Error::custom)?;
+        toml_update::update(original, &old, value).map_err(toml::ser::Error::custom)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
@@ -0,0 +1,318 @@
+#![cfg(feature = "fmt-properties")]
+
+use std::fmt::Write;
+
+use crate::Value;
+use crate::format::{Conversion, Format};
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Java [`.properties`](https://docs.oracle.com/javase/8/docs/api/java/util/Properties.html#load-java.io.Reader-) format.
+///
+/// Dotted keys are split into nested objects, so `database.pool.size = 10`
+/// becomes `{"database": {"pool": {"size": 10}}}`, and nested objects are
+/// flattened back when serializing. Dots that are part of a key are escaped as `\.`,
+/// e.g. `labels.app\.kubernetes\.io/name = x`.
+///
+/// Supports `#` and `!` comments, `=`, `:` and whitespace key separators,
+/// escape sequences (including `\uXXXX`) and lines continued with `\`.
+///
+/// Values are plain strings (see [`Conversion::Types`]) that are converted to the types
+/// of config fields, e.g. `port=5432` for a number or `hosts=["a", "b"]` for an array.
+///
+/// Serialization writes numbers and booleans as is and arrays as JSON.
+pub struct Properties;
+
+impl Format for Properties {
+    type ErrorDe = PropertiesError;
+    type ErrorSer = PropertiesError;
+
+    fn name() -> std::borrow::Cow<'static, str> {
+        "properties".into()
+    }
+
+    fn extensions() -> &'static [&'static str] {
+        &["properties"]
+    }
+
+    fn conversion() -> Conversion {
+        Conversion::Types
+    }
+
+    fn deserialize<T: serde::de::DeserializeOwned>(string: &str) -> Result<T, Self::ErrorDe> {
+        let mut root = serde_json::Map::new();
+
+        for (line_num, line) in logical_lines(string) {
+            let (key, value) = split_key_value(&line);
+            let to_parse_error = |message| PropertiesError::Parse {
+                message,
+                line: line_num,
+            };
+            let segments = split_key(key)
+                .into_iter()
+                .map(unescape)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(to_parse_error)?;
+            let value = unescape(value).map_err(to_parse_error)?;
+            let key = segments.join(".");
+
+            let mut obj = &mut root;
+            let mut segments = segments.iter().map(String::as_str).peekable();
+
+            while let Some(seg) = segments.next() {
+                if segments.peek().is_none() {
+                    if obj.get(seg).is_some_and(Value::is_object) {
+                        return Err(PropertiesError::Parse {
+                            message: format!("Key `{key}` conflicts with nested keys"),
+                            line: line_num,
+                        });
+                    }
+                    obj.insert(seg.to_string(), Value::String(value));
+                    break;
+                }
+
+                let entry = obj
+                    .entry(seg.to_string())
+                    .or_insert_with(|| Value::Object(Default::default()));
+
+                let Value::Object(child) = entry else {
+                    return Err(PropertiesError::Parse {
+                        message: format!("Key `{key}` conflicts with a value of key `{seg}`"),
+                        line: line_num,
+                    });
+                };
+                obj = child;
+            }
+        }
+
+        Ok(serde_json::from_value(Value::Object(root))?)
+    }
+
+    fn error_position(error: &Self::ErrorDe, _string: &str) -> Option<(usize, usize)> {
+        match error {
+            PropertiesError::Parse { line, .. } => Some((*line, 1)),
+            _ => None,
+        }
+    }
+
+    fn serialize<T: serde::ser::Serialize>(value: &T) -> Result<String, Self::ErrorSer> {
+        let Value::Object(root) = serde_json::to_value(value)? else {
+            return Err(PropertiesError::Unsupported(
+                "Only objects can be represented in properties format".to_string(),
+            ));
+        };
+
+        let mut buf = String::new();
+        write_props(&mut buf, "", &root)?;
+        Ok(buf)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Error returned by [`Properties`] format
+#[derive(Debug, thiserror::Error)]
+pub enum PropertiesError {
+    #[error("{message} at line {line}")]
+    Parse { message: String, line: usize },
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error("{0}")]
+    Unsupported(String),
+}
+
+impl From<std::fmt::Error> for PropertiesError {
+    fn from(value: std::fmt::Error) -> Self {
+        Self::Unsupported(value.to_string())
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+// Reading
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Joins continued lines and skips comments and blank lines, returning logical
+/// lines along with the line numbers where they start
+fn logical_lines(s: &str) -> Vec<(usize, String)> {
+    let mut ret = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+
+    for (i, line) in s.lines().enumerate() {
+        let line = line.trim_start_matches([' ', '\t', '\x0c']);
+
+        if current.is_none() && (line.is_empty() || line.starts_with(['#', '!'])) {
+            continue;
+        }
+
+        // Odd number of trailing backslashes means the line continues
+        let trailing = line.len() - line.trim_end_matches('\\').len();
+        let (line, continues) = if trailing % 2 == 1 {
+            (&line[..line.len() - 1], true)
+        } else {
+            (line, false)
+        };
+
+        let (_, buf) = current.get_or_insert_with(|| (i + 1, String::new()));
+        buf.push_str(line);
+
+        if !continues && let Some(l) = current.take() {
+            ret.push(l);
+        }
+    }
+
+    // Continuation on the last line
+    ret.extend(current);
+    ret
+}
+
+/// Splits the line at the first unescaped `=`, `:` or whitespace
+fn split_key_value(line: &str) -> (&str, &str) {
+    let mut escaped = false;
+    let mut key_end = line.len();
+
+    for (i, c) in line.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if matches!(c, '=' | ':' | ' ' | '\t' | '\x0c') {
+            key_end = i;
+            break;
+        }
+    }
+
+    let key = &line[..key_end];
+    let rest = line[key_end..].trim_start_matches([' ', '\t', '\x0c']);
+    let rest = rest.strip_prefix(['=', ':']).unwrap_or(rest);
+    let value = rest.trim_start_matches([' ', '\t', '\x0c']);
+
+    (key, value)
+}
+
+/// Splits the key at the unescaped `.`s, keeping the escapes of segments
+fn split_key(key: &str) -> Vec<&str> {
+    let mut ret = Vec::new();
+    let mut escaped = false;
+    let mut start = 0;
+
+    for (i, c) in key.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '.' {
+            ret.push(&key[start..i]);
+            start = i + 1;
+        }
+    }
+
+    ret.push(&key[start..]);
+    ret
+}
+
+fn unescape(s: &str) -> Result<String, String> {
+    let mut ret = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            ret.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('t') => ret.push('\t'),
+            Some('n') => ret.push('\n'),
+            Some('r') => ret.push('\r'),
+            Some('f') => ret.push('\x0c'),
+            Some('u') => {
+                let mut code = read_hex4(&mut chars)?;
+
+                // Characters outside of BMP are written as UTF-16 surrogate pairs
+                if (0xD800..0xDC00).contains(&code) && chars.as_str().starts_with("\\u") {
+                    chars.nth(1);
+                    let low = read_hex4(&mut chars)?;
+                    if (0xDC00..0xE000).contains(&low) {
+                        code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                    }
+                }
+
+                let c = char::from_u32(code)
+                    .ok_or_else(|| format!("Invalid escape sequence `\\u{code:04X}`"))?;
+                ret.push(c);
+            }
+            Some(c) => ret.push(c),
+            None => {}
+        }
+    }
+
+    Ok(ret)
+}
+
+fn read_hex4(chars: &mut std::str::Chars<'_>) -> Result<u32, String> {
+    let hex: String = chars.by_ref().take(4).collect();
+    u32::from_str_radix(&hex, 16).map_err(|_| format!("Invalid escape sequence `\\u{hex}`"))
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+// Writing
+/////////////////////////////////////////////////////////////////////////////////////////
+
+fn write_props(
+    buf: &mut String,
+    prefix: &str,
+    obj: &serde_json::Map<String, Value>,
+) -> Result<(), PropertiesError> {
+    for (key, value) in obj {
+        let key = format!("{prefix}{}", escape(key, true));
+
+        let value = match value {
+            // Absent values are the same as nulls
+            Value::Null => continue,
+            Value::Object(child) if !child.is_empty() => {
+                write_props(buf, &format!("{key}."), child)?;
+                continue;
+            }
+            Value::Object(_) | Value::Array(_) => serde_json::to_string(value)?,
+            Value::Bool(v) => v.to_string(),
+            Value::Number(v) => v.to_string(),
+            Value::String(v) => v.clone(),
+        };
+
+        writeln!(buf, "{key}={}", escape(&value, false))?;
+    }
+    Ok(())
+}
+
+fn escape(s: &str, is_key: bool) -> String {
+    let mut ret = String::with_capacity(s.len());
+    for (i, c) in s.chars().enumerate() {
+        match c {
+            '\\' => ret.push_str("\\\\"),
+            '\n' => ret.push_str("\\n"),
+            '\r' => ret.push_str("\\r"),
+            '\t' => ret.push_str("\\t"),
+            '\x0c' => ret.push_str("\\f"),
+            // Leading whitespace of values is trimmed when reading
+            ' ' if is_key || i == 0 => ret.push_str("\\ "),
+            // Dots separate the segments of nested keys
+            '=' | ':' | '.' if is_key => {
+                ret.push('\\');
+                ret.push(c);
+            }
+            '#' | '!' if i == 0 => {
+                ret.push('\\');
+                ret.push(c);
+            }
+            c if c.is_control() => {
+                let _ = write!(ret, "\\u{:04x}", c as u32);
+            }
+            c => ret.push(c),
+        }
+    }
+    ret
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
@@ -0,0 +1,88 @@
+#![cfg(feature = "fmt-ron")]
+
+use crate::Value;
+use crate::format::{Conversion, Format};
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// [RON](https://github.com/ron-rs/ron) format using `ron` crate.
+///
+/// Names of structs and enum variants are kept when reading, using serde's externally
+/// tagged representation (see [`Conversion::Names`]):
+/// - `Postgres(host: "x")` and `Postgres((host: "x"))` become `{"Postgres": {"host": "x"}}`
+/// - `Serial()` becomes `{"Serial": null}` and `Aes` becomes `"Aes"`
+/// - `(host: "x")` and `{"host": "x"}` become `{"host": "x"}`
+/// - `Some(x)` becomes `x`, while `None` and `()` become `null`
+/// - `(a, b)` tuples become arrays
+///
+/// [`crate::config::Config`] then uses the schema to map names onto setty's internally
+/// tagged enums, e.g. `{"kind": "Postgres", "host": "x"}`, and to drop names of
+/// structs, e.g. of the top-level `Config(...)`. Enums can also specify the tag
+/// explicitly, e.g. `(kind: "Postgres", host: "x")`.
+///
+/// Serialization is done by `ron` crate, so typed values are written as structs while
+/// [`Value`]s are written as maps.
+pub struct Ron;
+
+impl Format for Ron {
+    type ErrorDe = RonError;
+    type ErrorSer = RonError;
+
+    fn name() -> std::borrow::Cow<'static, str> {
+        "ron".into()
+    }
+
+    fn extensions() -> &'static [&'static str] {
+        &["ron"]
+    }
+
+    fn conversion() -> Conversion {
+        Conversion::Names
+    }
+
+    fn deserialize<T: serde::de::DeserializeOwned>(string: &str) -> Result<T, Self::ErrorDe> {
+        let Named::Value(value) = ron::from_str(string)?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    fn error_position(error: &Self::ErrorDe, _string: &str) -> Option<(usize, usize)> {
+        match error {
+            // Span ends at the offending character
+            RonError::Parse(e) => Some((e.span.end.line, e.span.end.col)),
+            _ => None,
+        }
+    }
+
+    fn serialize<T: serde::ser::Serialize>(value: &T) -> Result<String, Self::ErrorSer> {
+        let mut ron = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())?;
+        ron.push('\n');
+        Ok(ron)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Error returned by [`Ron`] format
+#[derive(Debug, thiserror::Error)]
+pub enum RonError {
+    #[error(transparent)]
+    Parse(#[from] ron::error::SpannedError),
+
+    #[error(transparent)]
+    Serialize(#[from] ron::Error),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// `ron` only reports names of structs and enum variants when deserializing into
+/// serde's internal buffer used by untagged enums, so the value is read through it
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum Named {
+    Value(Value),
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
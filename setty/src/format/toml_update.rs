@@ -0,0 +1,143 @@
+#![cfg(feature = "fmt-toml")]
+
+use toml_edit::{DocumentMut, Item, TableLike};
+
+use crate::Value;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Applies changes between the `old` and `new` values to the original TOML text,
+/// keeping comments, order and formatting of the untouched keys
+pub(crate) fn update(original: &str, old: &Value, new: &Value) -> Result<String, String> {
+    let mut doc: DocumentMut = original.parse().map_err(|e| format!("{e}"))?;
+
+    let (Some(old), Some(new)) = (old.as_object(), new.as_object()) else {
+        return Err("Only objects can be represented in TOML format".to_string());
+    };
+
+    sync_table(doc.as_table_mut(), old, new, false)?;
+
+    Ok(doc.to_string())
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+fn sync_table(
+    table: &mut dyn TableLike,
+    old: &serde_json::Map<String, Value>,
+    new: &serde_json::Map<String, Value>,
+    inline: bool,
+) -> Result<(), String> {
+    for key in old.keys() {
+        if new.get(key).is_none_or(Value::is_null) {
+            table.remove(key);
+        }
+    }
+
+    for (key, new_value) in new {
+        if new_value.is_null() {
+            continue;
+        }
+
+        let old_value = old.get(key);
+        if old_value == Some(new_value) {
+            continue;
+        }
+
+        // Descend into existing tables to only touch the changed keys
+        if let (Some(Value::Object(old_obj)), Value::Object(new_obj)) = (old_value, new_value)
+            && let Some(item) = table.get_mut(key)
+        {
+            let inline = inline || item.is_inline_table();
+            if let Some(child) = item.as_table_like_mut() {
+                sync_table(child, old_obj, new_obj, inline)?;
+                continue;
+            }
+        }
+
+        let mut new_item = to_item(new_value, inline)?;
+
+        // Replacing in place to keep the key formatting and comments around the value
+        if let Some(old_item) = table.get_mut(key) {
+            if let (Item::Value(old_value), Item::Value(v)) = (&*old_item, &mut new_item) {
+                *v.decor_mut() = old_value.decor().clone();
+            }
+            *old_item = new_item;
+        } else {
+            table.insert(key, new_item);
+        }
+    }
+
+    Ok(())
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+fn to_item(value: &Value, inline: bool) -> Result<Item, String> {
+    match value {
+        Value::Object(obj) if !inline => {
+            let mut table = toml_edit::Table::new();
+            let mut has_values = false;
+
+            for (k, v) in obj {
+                if v.is_null() {
+                    continue;
+                }
+                let item = to_item(v, false)?;
+                has_values |= item.is_value();
+                table.insert(k, item);
+            }
+
+            // Avoid empty headers of tables that only hold other tables
+            table.set_implicit(!has_values && !table.is_empty());
+            Ok(Item::Table(table))
+        }
+        Value::Array(items)
+            if !inline && !items.is_empty() && items.iter().all(Value::is_object) =>
+        {
+            let mut array = toml_edit::ArrayOfTables::new();
+            for item in items {
+                if let Item::Table(t) = to_item(item, false)? {
+                    array.push(t);
+                }
+            }
+            Ok(Item::ArrayOfTables(array))
+        }
+        value => Ok(Item::Value(to_value(value)?)),
+    }
+}
+
+fn to_value(value: &Value) -> Result<toml_edit::Value, String> {
+    Ok(match value {
+        Value::Null => return Err("TOML does not support null values".to_string()),
+        Value::Bool(v) => (*v).into(),
+        Value::Number(n) => {
+            if let Some(v) = n.as_i64() {
+                v.into()
+            } else if let Some(v) = n.as_f64()
+                && !n.is_u64()
+            {
+                v.into()
+            } else {
+                return Err(format!("Number {n} is out of range for TOML"));
+            }
+        }
+        Value::String(v) => v.as_str().into(),
+        Value::Array(items) => {
+            let mut array = toml_edit::Array::new();
+            for item in items.iter().filter(|v| !v.is_null()) {
+                array.push(to_value(item)?);
+            }
+            toml_edit::Value::Array(array)
+        }
+        Value::Object(obj) => {
+            let mut table = toml_edit::InlineTable::new();
+            for (k, v) in obj.iter().filter(|(_, v)| !v.is_null()) {
+                table.insert(k, to_value(v)?);
+            }
+            toml_edit::Value::InlineTable(table)
+        }
+    })
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
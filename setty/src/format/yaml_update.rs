@@ -0,0 +1,559 @@
+#![cfg(feature = "fmt-yaml")]
+
+use crate::Value;
+use crate::format::{Format, Yaml};
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Applies changes between the `old` and `new` values to the original YAML text
+/// by patching only the entries that changed, preserving comments, anchors and
+/// order of the rest of the document.
+///
+/// This is a best-effort text-level editor for block-style documents. It returns
+/// `None` when document uses constructs it doesn't understand (flow-style root,
+/// multiple documents, complex keys) or when patched document does not read back
+/// as the `new` value.
+pub(crate) fn update(original: &str, old: &Value, new: &Value) -> Option<String> {
+    if old == new {
+        return Some(original.to_string());
+    }
+
+    let Value::Object(new_obj) = new else {
+        return None;
+    };
+
+    let doc = Doc::new(original);
+    let mut edits = Vec::new();
+
+    let (from, indent) = doc.root()?;
+
+    match (old, from) {
+        (Value::Object(old_obj), Some(from)) => {
+            let entries = doc.parse_mapping(from, doc.lines.len(), indent)?;
+            doc.sync(&entries, old_obj, new_obj, indent, &mut edits)?;
+        }
+        // Document has only comments
+        (Value::Null, None) => {
+            let mut text = String::new();
+            if !original.is_empty() && !original.ends_with('\n') {
+                text.push('\n');
+            }
+            text.push_str(&Yaml::serialize(new).ok()?);
+            edits.push(Edit {
+                start: original.len(),
+                end: original.len(),
+                text,
+            });
+        }
+        _ => return None,
+    }
+
+    // Applying from the end, insertions at the same position keep their order
+    let mut ret = original.to_string();
+    let mut edits: Vec<_> = edits.into_iter().enumerate().collect();
+    edits.sort_by_key(|(i, e)| std::cmp::Reverse((e.start, *i)));
+    for (_, edit) in edits {
+        ret.replace_range(edit.start..edit.end, &edit.text);
+    }
+
+    // Verify the result to never write a document that means something else
+    let check: Value = Yaml::deserialize(&ret).ok()?;
+    (check == *new).then_some(ret)
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+struct Edit {
+    start: usize,
+    end: usize,
+    text: String,
+}
+
+struct Line {
+    /// Byte offset of the line start
+    start: usize,
+    /// Byte offset of the line end (excluding the newline)
+    end: usize,
+    indent: usize,
+    /// Whether line has anything except whitespace and comments
+    content: bool,
+}
+
+struct Entry {
+    key: String,
+    line: usize,
+    /// Byte offset right after the `:` separator
+    value_start: usize,
+    /// Index of the line after the last content line of the value
+    end_line: usize,
+    /// Whether the value starts on the next line (block mapping or sequence)
+    block: bool,
+}
+
+struct Item {
+    line: usize,
+    /// Byte offset right after the `-` marker
+    value_start: usize,
+    /// Index of the line after the last content line of the item
+    end_line: usize,
+}
+
+struct Doc<'a> {
+    text: &'a str,
+    lines: Vec<Line>,
+}
+
+impl<'a> Doc<'a> {
+    fn new(text: &'a str) -> Self {
+        let mut lines = Vec::new();
+        let mut start = 0;
+
+        for line in text.split_inclusive('\n') {
+            let body = line.trim_end_matches(['\n', '\r']);
+            let trimmed = body.trim_start_matches(' ');
+
+            lines.push(Line {
+                start,
+                end: start + body.len(),
+                indent: body.len() - trimmed.len(),
+                content: !trimmed.trim().is_empty() && !trimmed.starts_with('#'),
+            });
+            start += line.len();
+        }
+
+        Self { text, lines }
+    }
+
+    fn line_text(&self, i: usize) -> &'a str {
+        &self.text[self.lines[i].start..self.lines[i].end]
+    }
+
+    fn content(&self, i: usize) -> &'a str {
+        &self.line_text(i)[self.lines[i].indent..]
+    }
+
+    /// Returns the first line and indentation of the root mapping
+    fn root(&self) -> Option<(Option<usize>, usize)> {
+        let mut first = (0..self.lines.len()).find(|i| self.lines[*i].content);
+
+        // Allow explicit start of a single document
+        if let Some(i) = first
+            && let Some(rest) = self.content(i).strip_prefix("---")
+        {
+            let rest = rest.trim();
+            if !rest.is_empty() && !rest.starts_with('#') {
+                return None;
+            }
+            first = (i + 1..self.lines.len()).find(|i| self.lines[*i].content);
+        }
+
+        if let Some(i) = first
+            && self.content(i).starts_with('%')
+        {
+            return None;
+        }
+
+        Some((first, first.map_or(0, |i| self.lines[i].indent)))
+    }
+
+    fn parse_mapping(&self, from: usize, to: usize, indent: usize) -> Option<Vec<Entry>> {
+        let mut entries = Vec::new();
+        let mut i = from;
+
+        while i < to {
+            if !self.lines[i].content {
+                i += 1;
+                continue;
+            }
+            if self.lines[i].indent != indent {
+                return None;
+            }
+
+            let (key, value_offset) = parse_key(self.content(i))?;
+            let value_start = self.lines[i].start + indent + value_offset;
+            let (value, _) = split_comment(&self.text[value_start..self.lines[i].end]);
+            let block = is_block_start(value);
+
+            // Value spans all following lines that are indented deeper, or sequence
+            // items on the same level
+            let line = i;
+            let mut last = i;
+            i += 1;
+            while i < to {
+                let next = &self.lines[i];
+                if next.content && !(next.indent > indent || block && is_seq_item(self.content(i)))
+                {
+                    break;
+                }
+                if next.content {
+                    last = i;
+                }
+                i += 1;
+            }
+
+            entries.push(Entry {
+                key,
+                line,
+                value_start,
+                end_line: last + 1,
+                block,
+            });
+        }
+
+        Some(entries)
+    }
+
+    fn sync(
+        &self,
+        entries: &[Entry],
+        old: &serde_json::Map<String, Value>,
+        new: &serde_json::Map<String, Value>,
+        indent: usize,
+        edits: &mut Vec<Edit>,
+    ) -> Option<()> {
+        // Bail out on merge keys and other things we don't see in text
+        if entries.len() != old.len() || entries.iter().any(|e| !old.contains_key(&e.key)) {
+            return None;
+        }
+
+        for entry in entries {
+            let Some(new_value) = new.get(&entry.key) else {
+                edits.push(Edit {
+                    start: self.lines[entry.line].start,
+                    end: self.line_end_with_newline(entry.end_line - 1),
+                    text: String::new(),
+                });
+                continue;
+            };
+
+            let old_value = old.get(&entry.key);
+            if old_value == Some(new_value) {
+                continue;
+            }
+
+            // Descend into nested mappings to only touch the changed keys
+            if let (Some(Value::Object(old_obj)), Value::Object(new_obj)) = (old_value, new_value)
+                && entry.block
+                && !new_obj.is_empty()
+                && let Some(first) =
+                    (entry.line + 1..entry.end_line).find(|i| self.lines[*i].content)
+                && !is_seq_item(self.content(first))
+            {
+                let child_indent = self.lines[first].indent;
+                let children = self.parse_mapping(first, entry.end_line, child_indent)?;
+                self.sync(&children, old_obj, new_obj, child_indent, edits)?;
+                continue;
+            }
+
+            // Patch sequences item by item to keep comments of the other items
+            if let (Some(Value::Array(old_items)), Value::Array(new_items)) = (old_value, new_value)
+                && entry.block
+                && !new_items.is_empty()
+                && let Some(first) =
+                    (entry.line + 1..entry.end_line).find(|i| self.lines[*i].content)
+                && is_seq_item(self.content(first))
+            {
+                let item_indent = self.lines[first].indent;
+                let items = self.parse_sequence(first, entry.end_line, item_indent)?;
+                self.sync_sequence(&items, old_items, new_items, item_indent, edits)?;
+                continue;
+            }
+
+            let mut text = render_value(new_value, indent)?;
+
+            // Keep the comment after a single-line value
+            if !entry.block && entry.end_line == entry.line + 1 && !text.contains('\n') {
+                let (_, comment) =
+                    split_comment(&self.text[entry.value_start..self.lines[entry.line].end]);
+                text.push_str(comment);
+            }
+
+            edits.push(Edit {
+                start: entry.value_start,
+                end: self.lines[entry.end_line - 1].end,
+                text,
+            });
+        }
+
+        // New keys are appended after the last entry
+        let mut text = String::new();
+        for (key, value) in new {
+            if !old.contains_key(key) {
+                text.push('\n');
+                text.push_str(&" ".repeat(indent));
+                text.push_str(&render_key(key)?);
+                text.push(':');
+                text.push_str(&render_value(value, indent)?);
+            }
+        }
+        if !text.is_empty() {
+            let last = entries.last()?;
+            let pos = self.lines[last.end_line - 1].end;
+            edits.push(Edit {
+                start: pos,
+                end: pos,
+                text,
+            });
+        }
+
+        Some(())
+    }
+
+    fn parse_sequence(&self, from: usize, to: usize, indent: usize) -> Option<Vec<Item>> {
+        let mut items = Vec::new();
+        let mut i = from;
+
+        while i < to {
+            if !self.lines[i].content {
+                i += 1;
+                continue;
+            }
+            if self.lines[i].indent != indent || !is_seq_item(self.content(i)) {
+                return None;
+            }
+
+            // Item spans all following lines that are indented deeper
+            let line = i;
+            let mut last = i;
+            i += 1;
+            while i < to {
+                let next = &self.lines[i];
+                if next.content && next.indent <= indent {
+                    break;
+                }
+                if next.content {
+                    last = i;
+                }
+                i += 1;
+            }
+
+            items.push(Item {
+                line,
+                value_start: self.lines[line].start + indent + 1,
+                end_line: last + 1,
+            });
+        }
+
+        Some(items)
+    }
+
+    fn sync_sequence(
+        &self,
+        items: &[Item],
+        old: &[Value],
+        new: &[Value],
+        indent: usize,
+        edits: &mut Vec<Edit>,
+    ) -> Option<()> {
+        // Bail out on values we don't see in text
+        if items.len() != old.len() {
+            return None;
+        }
+
+        for (item, (old_value, new_value)) in items.iter().zip(old.iter().zip(new)) {
+            if old_value == new_value {
+                continue;
+            }
+
+            let mut text = render_item(new_value, indent)?;
+
+            // Keep the comment after a single-line item
+            if item.end_line == item.line + 1 && !text.contains('\n') {
+                let (_, comment) =
+                    split_comment(&self.text[item.value_start..self.lines[item.line].end]);
+                text.push_str(comment);
+            }
+
+            edits.push(Edit {
+                start: item.value_start,
+                end: self.lines[item.end_line - 1].end,
+                text,
+            });
+        }
+
+        // Removed items are at the end
+        if let Some(first) = items.get(new.len()) {
+            let last = items.last()?;
+            edits.push(Edit {
+                start: self.lines[first.line].start,
+                end: self.line_end_with_newline(last.end_line - 1),
+                text: String::new(),
+            });
+        }
+
+        // New items are appended after the last item
+        let mut text = String::new();
+        for value in new.iter().skip(old.len()) {
+            text.push('\n');
+            text.push_str(&" ".repeat(indent));
+            text.push('-');
+            text.push_str(&render_item(value, indent)?);
+        }
+        if !text.is_empty() {
+            let pos = self.lines[items.last()?.end_line - 1].end;
+            edits.push(Edit {
+                start: pos,
+                end: pos,
+                text,
+            });
+        }
+
+        Some(())
+    }
+
+    fn line_end_with_newline(&self, i: usize) -> usize {
+        self.lines
+            .get(i + 1)
+            .map_or(self.text.len(), |next| next.start)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Parses `key:` at the start of the line returning the key and the offset after `:`
+fn parse_key(s: &str) -> Option<(String, usize)> {
+    let key_end = match s.chars().next()? {
+        '"' | '\'' => {
+            let quote = s.as_bytes()[0];
+            let mut i = 1;
+            loop {
+                let c = *s.as_bytes().get(i)?;
+                if c == b'\\' && quote == b'"' {
+                    i += 2;
+                } else if c == quote {
+                    if quote == b'\'' && s.as_bytes().get(i + 1) == Some(&b'\'') {
+                        i += 2;
+                    } else {
+                        break i + 1;
+                    }
+                } else {
+                    i += 1;
+                }
+            }
+        }
+        '-' | '?' | '[' | '{' | '&' | '*' | '!' | '|' | '>' | '#' | '%' | '@' | '`' => {
+            return None;
+        }
+        _ => {
+            let bytes = s.as_bytes();
+            (0..bytes.len()).find(|i| {
+                bytes[*i] == b':' && matches!(bytes.get(i + 1), None | Some(b' ' | b'\t'))
+            })?
+        }
+    };
+
+    let rest = s[key_end..].trim_start_matches(' ');
+    let rest = rest.strip_prefix(':')?;
+    if !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+
+    let key: Value = Yaml::deserialize(&s[..key_end]).ok()?;
+    let key = match key {
+        Value::String(s) => s,
+        Value::Number(_) | Value::Bool(_) => s[..key_end].trim().to_string(),
+        _ => return None,
+    };
+
+    Some((key, s.len() - rest.len()))
+}
+
+/// Splits value from a trailing comment, returning comment with leading whitespace
+fn split_comment(s: &str) -> (&str, &str) {
+    let mut quote = None;
+    let mut prev = ' ';
+
+    for (i, c) in s.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if (c == '"' || c == '\'') && prev == ' ' => quote = Some(c),
+            None if c == '#' && prev.is_whitespace() => {
+                let value = s[..i].trim_end();
+                return (value, &s[value.len()..]);
+            }
+            None => {}
+        }
+        prev = c;
+    }
+
+    (s.trim_end(), "")
+}
+
+/// Whether inline part of the value means that value continues on the next lines
+fn is_block_start(value: &str) -> bool {
+    value
+        .split_whitespace()
+        .all(|t| t.starts_with('&') || t.starts_with('!'))
+}
+
+fn is_seq_item(content: &str) -> bool {
+    content == "-" || content.starts_with("- ")
+}
+
+fn render_key(key: &str) -> Option<String> {
+    let s = Yaml::serialize(&key).ok()?;
+    let s = s.trim_end_matches('\n');
+    (!s.contains('\n')).then(|| s.to_string())
+}
+
+/// Renders value to be placed after `key:` on the specified indentation level
+fn render_value(value: &Value, indent: usize) -> Option<String> {
+    let s = Yaml::serialize(value).ok()?;
+    let s = s.trim_end_matches('\n');
+
+    let mut lines = s.lines();
+    let first = lines.next().unwrap_or_default();
+
+    let mut ret = String::new();
+
+    if !is_compound(value) {
+        // Scalars and block scalars start on the same line
+        ret.push(' ');
+        ret.push_str(first);
+        for line in lines {
+            ret.push('\n');
+            ret.push_str(&" ".repeat(indent));
+            ret.push_str(line);
+        }
+    } else {
+        for line in s.lines() {
+            ret.push('\n');
+            ret.push_str(&" ".repeat(indent + 2));
+            ret.push_str(line);
+        }
+    }
+
+    Some(ret)
+}
+
+/// Renders value to be placed after `-` of a sequence item on the specified
+/// indentation level
+fn render_item(value: &Value, indent: usize) -> Option<String> {
+    let s = Yaml::serialize(value).ok()?;
+    let s = s.trim_end_matches('\n');
+
+    let mut ret = String::new();
+    for (i, line) in s.lines().enumerate() {
+        if i == 0 {
+            ret.push(' ');
+        } else {
+            ret.push('\n');
+            ret.push_str(&" ".repeat(indent + 2));
+        }
+        ret.push_str(line);
+    }
+
+    Some(ret)
+}
+
+/// Whether value is rendered as a block mapping or sequence, starting on the next line
+fn is_compound(value: &Value) -> bool {
+    match value {
+        Value::Object(obj) => !obj.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        _ => false,
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
@@ -0,0 +1,460 @@
+#![cfg(feature = "fmt-yaml")]
+
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::Deserialize as _;
+
+use crate::Value;
+use crate::format::{DynFormat, Format, Yaml};
+use crate::path::{KeyPath, Segment};
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Maximum nesting of `!include` tags, guards against include cycles
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// YAML format that resolves custom tags while loading using the registered
+/// [`YamlTag`] handlers. [`YamlWithTags::new()`] registers:
+/// - `!env DB_HOST` - value of an environment variable, parsed as YAML scalar
+///   so `!env PORT` can be used for numeric fields
+/// - `!file secrets/pw` - contents of a file as a string (without trailing newline)
+/// - `!include db.yaml` - contents of another YAML file, that can use tags too
+///
+/// Relative paths are resolved against the directory of the file that contains the
+/// tag, or against the current working directory when the data is not read from a
+/// file, e.g. via [`crate::source::RawData`].
+///
+/// The format is chosen at runtime via [`crate::source::File::with_format()`] or
+/// [`crate::format::FormatRegistry::with_dyn_format()`]. It is named `yaml-tags` but
+/// handles the same file extensions as [`Yaml`], so registering it after [`Yaml`] makes
+/// it the format of `.yaml` files while `yaml` name still refers to plain [`Yaml`].
+///
+/// Tags are not preserved when serializing, and documents that contain tags can't be
+/// updated, so that resolved values, e.g. secrets, are never written to them.
+#[derive(Clone)]
+pub struct YamlWithTags {
+    tags: Vec<Arc<dyn YamlTag>>,
+}
+
+impl YamlWithTags {
+    /// Creates format with `!env`, `!file` and `!include` tag handlers
+    pub fn new() -> Self {
+        Self::empty()
+            .with_tag(EnvTag)
+            .with_tag(FileTag)
+            .with_tag(IncludeTag)
+    }
+
+    /// Creates format without any tag handlers
+    pub fn empty() -> Self {
+        Self { tags: Vec::new() }
+    }
+
+    /// Registers a tag handler, replacing the one with the same name
+    pub fn with_tag(mut self, tag: impl YamlTag + 'static) -> Self {
+        self.tags.retain(|t| t.name() != tag.name());
+        self.tags.push(Arc::new(tag));
+        self
+    }
+
+    /// Parses a YAML document resolving the tags
+    pub fn deserialize<T: serde::de::DeserializeOwned>(
+        &self,
+        string: &str,
+    ) -> Result<T, YamlWithTagsError> {
+        let value = TagContext::new(self, None).parse(string)?;
+        Ok(serde_yaml::from_value(value)?)
+    }
+
+    /// Parses all `---` separated YAML documents resolving the tags, using `base_dir` to
+    /// resolve relative paths
+    fn deserialize_documents_in(
+        &self,
+        string: &str,
+        base_dir: Option<&Path>,
+    ) -> Result<Vec<Value>, YamlWithTagsError> {
+        let mut ctx = TagContext::new(self, base_dir);
+
+        serde_yaml::Deserializer::from_str(string)
+            .map(|doc| {
+                let value = serde_yaml::Value::deserialize(doc)?;
+                let value = ctx.resolve(value)?;
+                Ok(serde_yaml::from_value(value)?)
+            })
+            .collect()
+    }
+
+    fn resolve_tag(
+        &self,
+        tag: &str,
+        value: serde_yaml::Value,
+        ctx: &mut TagContext<'_>,
+    ) -> Result<serde_yaml::Value, YamlWithTagsError> {
+        let Some(handler) = self.tags.iter().find(|t| t.name() == tag) else {
+            return Err(YamlWithTagsError::UnknownTag {
+                tag: tag.to_string(),
+                path: ctx.path(),
+            });
+        };
+
+        handler
+            .resolve(value, ctx)
+            .map_err(|error| YamlWithTagsError::Tag {
+                tag: tag.to_string(),
+                path: ctx.path(),
+                error,
+            })
+    }
+}
+
+impl Default for YamlWithTags {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<YamlWithTags> for Arc<dyn DynFormat> {
+    fn from(value: YamlWithTags) -> Self {
+        Arc::new(value)
+    }
+}
+
+impl DynFormat for YamlWithTags {
+    fn name(&self) -> Cow<'static, str> {
+        "yaml-tags".into()
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        Yaml::extensions()
+    }
+
+    fn deserialize(&self, string: &str) -> Result<Value, BoxedError> {
+        Ok(YamlWithTags::deserialize(self, string)?)
+    }
+
+    fn deserialize_documents(&self, string: &str) -> Result<Vec<Value>, BoxedError> {
+        Ok(self.deserialize_documents_in(string, None)?)
+    }
+
+    fn deserialize_file(&self, path: &Path, string: &str) -> Result<Value, BoxedError> {
+        let value = TagContext::new(self, path.parent()).parse(string)?;
+        Ok(serde_yaml::from_value(value).map_err(YamlWithTagsError::from)?)
+    }
+
+    fn deserialize_file_documents(
+        &self,
+        path: &Path,
+        string: &str,
+    ) -> Result<Vec<Value>, BoxedError> {
+        Ok(self.deserialize_documents_in(string, path.parent())?)
+    }
+
+    /// Files read by `!include`, `!file` and custom tags using [`TagContext::read_file()`],
+    /// including ones that fail to be read so they are watched until created
+    fn file_dependencies(&self, path: &Path, string: &str) -> Vec<PathBuf> {
+        let mut ctx = TagContext::new(self, path.parent());
+
+        for doc in serde_yaml::Deserializer::from_str(string) {
+            if let Ok(value) = serde_yaml::Value::deserialize(doc) {
+                let _ = ctx.resolve(value);
+            }
+        }
+
+        ctx.files
+    }
+
+    fn serialize(&self, value: &Value) -> Result<String, BoxedError> {
+        Ok(Yaml::serialize(value)?)
+    }
+
+    fn error_position(
+        &self,
+        error: &(dyn std::error::Error + Send + Sync + 'static),
+        string: &str,
+    ) -> Option<(usize, usize)> {
+        match error.downcast_ref::<YamlWithTagsError>()? {
+            YamlWithTagsError::Yaml(e) => Yaml::error_position(e, string),
+            _ => None,
+        }
+    }
+
+    fn key_position(&self, string: &str, path: &KeyPath) -> Option<(usize, usize)> {
+        Yaml::key_position(string, path)
+    }
+
+    fn update(&self, original: &str, value: &Value) -> Result<String, BoxedError> {
+        for doc in serde_yaml::Deserializer::from_str(original) {
+            let doc = serde_yaml::Value::deserialize(doc).map_err(YamlWithTagsError::from)?;
+            if has_tags(&doc) {
+                return Err(YamlWithTagsError::UpdateTagged.into());
+            }
+        }
+        Ok(Yaml::update(original, value)?)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Error returned by [`YamlWithTags`] format
+#[derive(Debug, thiserror::Error)]
+pub enum YamlWithTagsError {
+    #[error(transparent)]
+    Yaml(#[from] serde_yaml::Error),
+
+    #[error("Unknown tag `!{tag}` at `{path}`")]
+    UnknownTag { tag: String, path: String },
+
+    #[error("Failed to resolve tag `!{tag}` at `{path}`: {error}")]
+    Tag {
+        tag: String,
+        path: String,
+        error: BoxedError,
+    },
+
+    #[error("Includes are nested deeper than {0} levels")]
+    IncludeDepth(usize),
+
+    #[error("Document with tags can't be updated without writing the resolved values")]
+    UpdateTagged,
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Handler of a custom YAML tag registered via [`YamlWithTags::with_tag()`]
+pub trait YamlTag: Send + Sync {
+    /// Name of the tag without the leading `!`, e.g. `env`
+    fn name(&self) -> &str;
+
+    /// Replaces the tagged value with the resolved one
+    fn resolve(
+        &self,
+        value: serde_yaml::Value,
+        ctx: &mut TagContext<'_>,
+    ) -> Result<serde_yaml::Value, BoxedError>;
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// State of tag resolution passed to [`YamlTag`] handlers
+pub struct TagContext<'a> {
+    format: &'a YamlWithTags,
+    path: KeyPath,
+    base_dir: Option<PathBuf>,
+    depth: usize,
+    files: Vec<PathBuf>,
+}
+
+impl<'a> TagContext<'a> {
+    fn new(format: &'a YamlWithTags, base_dir: Option<&Path>) -> Self {
+        Self {
+            format,
+            path: KeyPath::new(),
+            base_dir: base_dir.map(Path::to_path_buf),
+            depth: 0,
+            files: Vec::new(),
+        }
+    }
+
+    /// Path of the value being resolved, e.g. `database.password`
+    pub fn path(&self) -> String {
+        if self.path.segments().is_empty() {
+            "<root>".to_string()
+        } else {
+            self.path.to_string()
+        }
+    }
+
+    /// Resolves a path relative to the directory of the file being parsed
+    pub fn resolve_path(&self, path: impl AsRef<Path>) -> PathBuf {
+        match &self.base_dir {
+            Some(dir) => dir.join(path),
+            None => path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Parses a YAML document resolving tags in it
+    pub fn parse(&mut self, s: &str) -> Result<serde_yaml::Value, YamlWithTagsError> {
+        if self.depth > MAX_INCLUDE_DEPTH {
+            return Err(YamlWithTagsError::IncludeDepth(MAX_INCLUDE_DEPTH));
+        }
+
+        self.depth += 1;
+        let res = serde_yaml::from_str(s)
+            .map_err(YamlWithTagsError::from)
+            .and_then(|value| self.resolve(value));
+        self.depth -= 1;
+        res
+    }
+
+    /// Reads a file relative to the directory of the file being parsed, so that the
+    /// config is reloaded when it changes (see [`crate::Config::watch()`])
+    pub fn read_file(&mut self, path: impl AsRef<Path>) -> Result<String, BoxedError> {
+        let path = self.resolve_path(path);
+        self.read_resolved(&path)
+    }
+
+    fn read_resolved(&mut self, path: &Path) -> Result<String, BoxedError> {
+        self.files.push(path.to_path_buf());
+        std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read file `{}`: {e}", path.display()).into())
+    }
+
+    /// Parses a YAML file resolving tags in it, with relative paths in the file
+    /// resolved against its directory
+    pub fn parse_file(&mut self, path: impl AsRef<Path>) -> Result<serde_yaml::Value, BoxedError> {
+        let path = self.resolve_path(path);
+        let contents = self.read_resolved(&path)?;
+
+        let base_dir = std::mem::replace(&mut self.base_dir, path.parent().map(Path::to_path_buf));
+        let res = self.parse(&contents);
+        self.base_dir = base_dir;
+        Ok(res?)
+    }
+
+    fn resolve(
+        &mut self,
+        value: serde_yaml::Value,
+    ) -> Result<serde_yaml::Value, YamlWithTagsError> {
+        use serde_yaml::Value;
+
+        match value {
+            Value::Tagged(tagged) => {
+                let tag = tagged.tag.to_string();
+                let tag = tag.strip_prefix('!').unwrap_or(&tag);
+
+                let value = self.resolve(tagged.value)?;
+                let format = self.format;
+                format.resolve_tag(tag, value, self)
+            }
+            Value::Mapping(mapping) => {
+                let mut ret = serde_yaml::Mapping::with_capacity(mapping.len());
+                for (k, v) in mapping {
+                    self.path.push(Segment::Key(match &k {
+                        Value::String(s) => s.clone(),
+                        Value::Number(n) => n.to_string(),
+                        Value::Bool(b) => b.to_string(),
+                        _ => "?".to_string(),
+                    }));
+                    let v = self.resolve(v);
+                    self.path.pop();
+                    ret.insert(k, v?);
+                }
+                Ok(Value::Mapping(ret))
+            }
+            Value::Sequence(seq) => {
+                let mut ret = Vec::with_capacity(seq.len());
+                for (i, v) in seq.into_iter().enumerate() {
+                    self.path.push(Segment::Index(i));
+                    let v = self.resolve(v);
+                    self.path.pop();
+                    ret.push(v?);
+                }
+                Ok(Value::Sequence(ret))
+            }
+            value => Ok(value),
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+fn has_tags(value: &serde_yaml::Value) -> bool {
+    match value {
+        serde_yaml::Value::Tagged(_) => true,
+        serde_yaml::Value::Sequence(items) => items.iter().any(has_tags),
+        serde_yaml::Value::Mapping(map) => map.iter().any(|(k, v)| has_tags(k) || has_tags(v)),
+        _ => false,
+    }
+}
+
+fn expect_str(value: &serde_yaml::Value) -> Result<&str, BoxedError> {
+    value
+        .as_str()
+        .ok_or_else(|| "Expected a string argument".into())
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// `!env NAME` tag that reads value from an environment variable
+pub struct EnvTag;
+
+impl YamlTag for EnvTag {
+    fn name(&self) -> &str {
+        "env"
+    }
+
+    fn resolve(
+        &self,
+        value: serde_yaml::Value,
+        _ctx: &mut TagContext<'_>,
+    ) -> Result<serde_yaml::Value, BoxedError> {
+        let name = expect_str(&value)?;
+
+        let Ok(var) = std::env::var(name) else {
+            return Err(
+                format!("Environment variable `{name}` is not set or is not unicode").into(),
+            );
+        };
+
+        // Only accepting scalars to not turn arbitrary strings into objects
+        match serde_yaml::from_str(&var) {
+            Ok(v @ (serde_yaml::Value::Bool(_) | serde_yaml::Value::Number(_))) => Ok(v),
+            _ => Ok(serde_yaml::Value::String(var)),
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// `!file PATH` tag that reads value from a file, e.g. a secret
+pub struct FileTag;
+
+impl YamlTag for FileTag {
+    fn name(&self) -> &str {
+        "file"
+    }
+
+    fn resolve(
+        &self,
+        value: serde_yaml::Value,
+        ctx: &mut TagContext<'_>,
+    ) -> Result<serde_yaml::Value, BoxedError> {
+        let mut contents = ctx.read_file(expect_str(&value)?)?;
+
+        if contents.ends_with('\n') {
+            contents.pop();
+            if contents.ends_with('\r') {
+                contents.pop();
+            }
+        }
+
+        Ok(serde_yaml::Value::String(contents))
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// `!include PATH` tag that inserts contents of another YAML file
+pub struct IncludeTag;
+
+impl YamlTag for IncludeTag {
+    fn name(&self) -> &str {
+        "include"
+    }
+
+    fn resolve(
+        &self,
+        value: serde_yaml::Value,
+        ctx: &mut TagContext<'_>,
+    ) -> Result<serde_yaml::Value, BoxedError> {
+        ctx.parse_file(expect_str(&value)?)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
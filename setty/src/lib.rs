@@ -130,10 +130,21 @@
 //!     # Pick input format(s)
 //!     "fmt-toml",
 //!     "fmt-json",
+//!     "fmt-json5",
 //!     "fmt-yaml",
+//!     "fmt-ini",
+//!     "fmt-properties",
+//!     "fmt-ron",
+//!     "fmt-hcl",
 //!     # Pick generation target formats
 //!     "gen-jsonschema",
 //!     "gen-markdown",
+//!     # Keep keys in order of source files and struct fields instead of sorting them
+//!     "preserve-order",
+//!     # Render errors with snippets of config files and help text (see `diagnostics::Report`)
+//!     "diagnostics",
+//!     # Reload config when its files change (see `Config::watch()`)
+//!     "watch",
 //!     # Extra types support
 //!     "types-bigdecimal",
 //!     "types-chrono",
@@ -147,7 +158,7 @@
 //! Finally, load the config:
 //! ```ignore
 //! use setty::format::{Toml, Yaml};
-//! use setty::source::{File, Env};
+//! use setty::source::{File, Env, Overrides};
 //!
 //! let cfg: AppConfig = setty::Config::new()
 //!     // Specify sources in priority order. Latter sources replace or merge
@@ -161,6 +172,8 @@
 //!     //
 //!     // I switch to YAML for env vars to avoid excessive quotes in most cases
 //!     .with_source(Env::<Yaml>::new("APP_CONFIG__", "__"))
+//!     // Overrides passed via CLI like `--set 'labels."app.kubernetes.io/name"=web'`
+//!     .with_source(Overrides::<Yaml>::new(args.overrides))
 //!     // Merges the values and deserializes to config type
 //!     .extract()?;
 //! ```
@@ -174,26 +187,37 @@
 //!
 //!
 //! ## Limitations and Future Ideas
-//! - Config editing currently does not preserve order, comments, and formatting of files
+//! - Config editing currently does not preserve comments and formatting of files (except for TOML and YAML), and preserves order of keys only with `preserve-order` feature
 //! - It's not possible to use different case convention for different formats (e.g. `camelCase` for YAML and `kebab-case` for TOML) - we could support it as a runtime (pre-processing) option
 //! - Provide less verbose default syntax like `user: String = "root"` when/if the syntax is [stabilized](https://github.com/rust-lang/rust/issues/132162)
 //!   - Currently it's not possible to support this even with a proc macro because how `rustc` rushes to parse the struct definition before handing it over to attribute macro
 //! - Ability to provide example values that will appear in JSON Schema and Markdown
 
 mod check_deprecated;
+mod check_schema;
+mod coerce;
 pub mod combine;
 pub mod config;
+pub mod diagnostics;
+pub mod diff;
+pub mod env_vars;
 pub mod errors;
 pub mod format;
+mod locate;
 pub mod markdown;
 mod merge_with_defaults;
+pub mod path;
+pub mod reload;
 pub mod schema;
+mod schema_helpers;
 pub mod source;
 pub mod types;
+pub mod watch;
 
 /////////////////////////////////////////////////////////////////////////////////////////
 
 pub use config::Config;
+pub use diff::diff;
 
 pub use serde_json::Value;
 
@@ -0,0 +1,704 @@
+#![cfg(feature = "derive-deserialize")]
+
+use std::ops::Range;
+
+use crate::Value;
+use crate::errors::{Location, ReadError};
+use crate::path::{self, KeyPath};
+use crate::source::Layer;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Deserializes the merged value tracing errors back to the layer and the line
+/// where the offending key was set
+pub(crate) fn deserialize<T: serde::de::DeserializeOwned>(
+    value: Value,
+    layers: &[Layer],
+) -> Result<T, ReadError> {
+    deserialize_at(value, &KeyPath::new(), layers)
+}
+
+/// Same as [`deserialize()`] for the value found under the `prefix` path of the merged
+/// value
+pub(crate) fn deserialize_at<T: serde::de::DeserializeOwned>(
+    value: Value,
+    prefix: &KeyPath,
+    layers: &[Layer],
+) -> Result<T, ReadError> {
+    serde_path_to_error::deserialize(value).map_err(|e| {
+        let mut inner = e.path().iter().filter_map(|s| match s {
+            serde_path_to_error::Segment::Seq { index } => Some(Segment::Index(*index)),
+            serde_path_to_error::Segment::Map { key } => Some(Segment::Key(key)),
+            serde_path_to_error::Segment::Enum { variant } => Some(Segment::Key(variant)),
+            serde_path_to_error::Segment::Unknown => None,
+        });
+
+        // Wildcards are filled with the keys of the offending entries
+        let mut segments = Vec::new();
+        for segment in prefix.segments() {
+            match segment {
+                path::Segment::Key(key) => segments.push(Segment::Key(key)),
+                path::Segment::Index(index) => segments.push(Segment::Index(*index)),
+                path::Segment::Wildcard => match inner.next() {
+                    Some(segment) => segments.push(segment),
+                    None => break,
+                },
+            }
+        }
+        segments.extend(inner);
+
+        let location = locate(&segments, layers).map(Box::new);
+        let path = format_path(&segments);
+
+        classify(path, location, e.into_inner())
+    })
+}
+
+/// Borrows segments of the path that has no wildcards
+#[cfg(all(feature = "derive-jsonschema", feature = "derive-validate"))]
+pub(crate) fn segments(path: &KeyPath) -> Vec<Segment<'_>> {
+    path.segments()
+        .iter()
+        .filter_map(|s| match s {
+            path::Segment::Key(key) => Some(Segment::Key(key)),
+            path::Segment::Index(index) => Some(Segment::Index(*index)),
+            path::Segment::Wildcard => None,
+        })
+        .collect()
+}
+
+/// Collects failures of all fields into [`ReadError::FieldValidation`] errors, ordered
+/// by path
+#[cfg(all(feature = "derive-jsonschema", feature = "derive-validate"))]
+pub(crate) fn flatten_validation_errors<'a>(
+    path: &mut Vec<Segment<'a>>,
+    errs: &'a validator::ValidationErrors,
+    layers: &[Layer],
+    ret: &mut Vec<ReadError>,
+) {
+    use validator::ValidationErrorsKind;
+
+    let mut fields: Vec<_> = errs.errors().iter().collect();
+    fields.sort_by_key(|(a, _)| *a);
+
+    for (field, kind) in fields {
+        path.push(Segment::Key(field));
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                for err in errors {
+                    ret.push(ReadError::FieldValidation {
+                        path: format_path(path),
+                        code: err.code.to_string(),
+                        message: validation_message(err),
+                        location: locate(path, layers).map(Box::new),
+                    });
+                }
+            }
+            ValidationErrorsKind::Struct(errs) => {
+                flatten_validation_errors(path, errs, layers, ret);
+            }
+            ValidationErrorsKind::List(items) => {
+                for (i, errs) in items {
+                    path.push(Segment::Index(*i));
+                    flatten_validation_errors(path, errs, layers, ret);
+                    path.pop();
+                }
+            }
+        }
+        path.pop();
+    }
+}
+
+/// Uses the custom message, or describes the rule, e.g. `length (min = 5)`
+#[cfg(all(feature = "derive-jsonschema", feature = "derive-validate"))]
+fn validation_message(err: &validator::ValidationError) -> String {
+    if let Some(message) = &err.message {
+        return message.to_string();
+    }
+
+    let mut params: Vec<_> = err
+        .params
+        .iter()
+        .filter(|(k, _)| *k != "value")
+        .map(|(k, v)| format!("{k} = {v}"))
+        .collect();
+    params.sort();
+
+    if params.is_empty() {
+        err.code.to_string()
+    } else {
+        format!("{} ({})", err.code, params.join(", "))
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Turns standard serde error messages into structured errors.
+///
+/// Values of all formats are merged before deserializing, so errors always come from
+/// `serde_json::Value` deserializer, which formats them with default methods of
+/// [`serde::de::Error`], e.g. ``unknown field `x`, expected `a` or `b` ``. Shapes of these
+/// messages are pinned by tests for every format.
+fn classify(path: String, location: Option<Box<Location>>, error: serde_json::Error) -> ReadError {
+    let message = error.to_string();
+
+    if let Some(rest) = message.strip_prefix("unknown field ") {
+        let (field, expected) = split_names(rest);
+        let field = field.unwrap_or_default();
+        let similar = suggest(&field, &expected);
+
+        // Path stops at the containing object for fields of internally tagged enums
+        let path = if path.is_empty() {
+            field
+        } else if path == field || path.ends_with(&format!(".{field}")) {
+            path
+        } else {
+            format!("{path}.{field}")
+        };
+
+        let suggestions = similar.iter().map(|s| sibling_path(&path, s)).collect();
+
+        ReadError::UnknownField {
+            path,
+            expected,
+            suggestions,
+            location,
+        }
+    } else if let Some(rest) = message.strip_prefix("unknown variant ") {
+        let (variant, expected) = split_names(rest);
+        ReadError::UnknownVariant {
+            path,
+            variant: variant.unwrap_or_default(),
+            expected,
+            location,
+        }
+    } else if let Some(field) = message
+        .strip_prefix("missing field `")
+        .and_then(|s| s.strip_suffix('`'))
+    {
+        let path = if path.is_empty() {
+            field.to_string()
+        } else {
+            format!("{path}.{field}")
+        };
+        ReadError::MissingField { path, location }
+    } else if let Some((found, expected)) = message
+        .strip_prefix("invalid type: ")
+        .and_then(|s| s.split_once(", expected "))
+    {
+        ReadError::InvalidType {
+            path,
+            expected: expected.to_string(),
+            found: found.to_string(),
+            location,
+        }
+    } else if let Some((found, expected)) = message
+        .strip_prefix("invalid value: ")
+        .and_then(|s| s.split_once(", expected "))
+    {
+        ReadError::InvalidValue {
+            path,
+            expected: expected.to_string(),
+            found: found.to_string(),
+            location,
+        }
+    } else {
+        ReadError::Deserialize {
+            path,
+            location,
+            source: error.into(),
+        }
+    }
+}
+
+/// Replaces the last key of the dotted path
+pub(crate) fn sibling_path(path: &str, name: &str) -> String {
+    match path.rsplit_once('.') {
+        Some((parent, _)) => format!("{parent}.{name}"),
+        None => name.to_string(),
+    }
+}
+
+/// Splits "`name`, expected `a`, `b`" into the name and expected names
+fn split_names(s: &str) -> (Option<String>, Vec<String>) {
+    let (name, expected) = s.split_once(", ").unwrap_or((s, ""));
+
+    let name = name
+        .strip_prefix('`')
+        .and_then(|n| n.strip_suffix('`'))
+        .map(str::to_string);
+
+    let expected = expected
+        .split('`')
+        .skip(1)
+        .step_by(2)
+        .map(str::to_string)
+        .collect();
+
+    (name, expected)
+}
+
+/// Expected names similar to the unknown one, closest first. Names that differ only
+/// in case convention (e.g. `schemaName` and `schema_name`) are the closest.
+pub(crate) fn suggest(name: &str, expected: &[String]) -> Vec<String> {
+    let name = normalize(name);
+    let max_distance = (name.chars().count() / 3).max(1);
+
+    let mut ret: Vec<_> = expected
+        .iter()
+        .map(|e| (strsim::damerau_levenshtein(&name, &normalize(e)), e))
+        .filter(|(d, _)| *d <= max_distance)
+        .collect();
+    ret.sort_by_key(|(d, _)| *d);
+
+    // Variants of enums may have same fields
+    let mut names: Vec<String> = Vec::new();
+    for (_, e) in ret {
+        if !names.contains(e) && names.len() < 3 {
+            names.push(e.clone());
+        }
+    }
+    names
+}
+
+/// Suggests similar paths for the unknown one using all paths of the schema (see
+/// [`crate::Config::complete_path`]): fields of the same parent with similar names,
+/// or fields with the same name in other parents when key was put on a wrong level.
+///
+/// Returns `None` when parent of the unknown field is not known to the schema,
+/// e.g. when it is an element of an array.
+#[cfg(feature = "derive-jsonschema")]
+pub(crate) fn suggest_paths(path: &str, paths: &[String]) -> Option<Vec<String>> {
+    let (parent, name) = match path.rsplit_once('.') {
+        Some((parent, name)) => (Some(parent), name),
+        None => (None, path),
+    };
+    if parent.is_some_and(|p| p.contains('[')) {
+        return None;
+    }
+
+    fn parent_of(p: &str) -> Option<&str> {
+        p.rsplit_once('.').map(|(parent, _)| parent)
+    }
+    fn name_of(p: &str) -> String {
+        p.rsplit_once('.').map_or(p, |(_, name)| name).to_string()
+    }
+
+    if parent.is_some() && !paths.iter().any(|p| Some(p.as_str()) == parent) {
+        return None;
+    }
+
+    let siblings: Vec<_> = paths
+        .iter()
+        .filter(|p| parent_of(p) == parent)
+        .map(|p| name_of(p))
+        .collect();
+
+    let ret: Vec<_> = suggest(name, &siblings)
+        .into_iter()
+        .map(|s| match parent {
+            Some(parent) => format!("{parent}.{s}"),
+            None => s,
+        })
+        .collect();
+    if !ret.is_empty() {
+        return Some(ret);
+    }
+
+    let normalized = normalize(name);
+    let mut ret: Vec<String> = Vec::new();
+    for p in paths {
+        if parent_of(p) != parent && normalize(&name_of(p)) == normalized && !ret.contains(p) {
+            ret.push(p.clone());
+        }
+    }
+    ret.truncate(3);
+    Some(ret)
+}
+
+/// Name in lower case without separators, to compare names in different case conventions
+pub(crate) fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+pub(crate) enum Segment<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+/// Finds the last layer that sets the longest prefix of the path
+pub(crate) fn locate(segments: &[Segment<'_>], layers: &[Layer]) -> Option<Location> {
+    for len in (1..=segments.len()).rev() {
+        let prefix = &segments[..len];
+
+        let Some(layer) = layers
+            .iter()
+            .rev()
+            .find(|l| lookup(&l.value, prefix).is_some())
+        else {
+            continue;
+        };
+
+        let location = layer
+            .text
+            .as_deref()
+            .and_then(|text| {
+                let position = layer
+                    .format
+                    .as_ref()
+                    .and_then(|f| f.key_position(text, &to_key_path(prefix)));
+
+                match position {
+                    Some((line, col)) => Some(Location::at(layer.name.clone(), text, line, col)),
+                    None => {
+                        let offset = locate_key(text, prefix)?;
+                        Some(Location::at_offset(layer.name.clone(), text, offset))
+                    }
+                }
+            })
+            .unwrap_or_else(|| Location::new(layer.name.clone()));
+
+        return Some(location);
+    }
+
+    None
+}
+
+/// Formats the path using [`KeyPath`] syntax, e.g. `items[0].name`
+pub(crate) fn format_path(segments: &[Segment<'_>]) -> String {
+    to_key_path(segments).to_string()
+}
+
+fn to_key_path(segments: &[Segment<'_>]) -> KeyPath {
+    segments
+        .iter()
+        .map(|s| match s {
+            Segment::Key(key) => path::Segment::Key(key.to_string()),
+            Segment::Index(index) => path::Segment::Index(*index),
+        })
+        .collect()
+}
+
+fn lookup<'a>(value: &'a Value, segments: &[Segment<'_>]) -> Option<&'a Value> {
+    segments.iter().try_fold(value, |v, s| match s {
+        Segment::Key(k) => v.as_object()?.get(*k),
+        Segment::Index(i) => v.as_array()?.get(*i),
+    })
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Best-effort fallback for formats that don't report positions of keys (see
+/// [`crate::format::Format::key_position()`]), or when the key is not in the text,
+/// e.g. when it comes from an `!include`. Format-agnostic search of the key path in
+/// the text, that looks for each key within the value of its parent key skipping
+/// comments. Returns byte offset of the deepest key found.
+fn locate_key(text: &str, segments: &[Segment<'_>]) -> Option<usize> {
+    // Elements of arrays are searched in the whole array
+    let keys: Vec<&str> = segments
+        .iter()
+        .filter_map(|s| match s {
+            Segment::Key(key) => Some(*key),
+            Segment::Index(_) => None,
+        })
+        .collect();
+
+    let search = KeySearch {
+        text,
+        comments: comment_ranges(text),
+    };
+
+    let mut deepest = None;
+    search.find_path(&keys, 0..text.len(), false, 0, &mut deepest);
+    deepest.map(|(_, offset)| offset)
+}
+
+struct KeySearch<'a> {
+    text: &'a str,
+    comments: Vec<Range<usize>>,
+}
+
+/// Key found in the text
+struct KeyMatch {
+    /// Offset of the key or of its opening quote
+    start: usize,
+    /// Offset after the key and its closing quote
+    end: usize,
+}
+
+impl KeySearch<'_> {
+    /// Tries occurrences of the first key within the range until the rest of the keys
+    /// are found within its value, recording the deepest key found along the way
+    fn find_path(
+        &self,
+        keys: &[&str],
+        range: Range<usize>,
+        anchored: bool,
+        level: usize,
+        deepest: &mut Option<(usize, usize)>,
+    ) -> bool {
+        let Some((key, rest)) = keys.split_first() else {
+            return true;
+        };
+
+        for m in self.find_key(range.clone(), anchored, key) {
+            if deepest.is_none_or(|(depth, _)| depth <= level) {
+                *deepest = Some((level + 1, m.start));
+            }
+
+            let (value, anchored) = self.value_range(&m, range.end);
+            if self.find_path(rest, value, anchored, level + 1, deepest) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Occurrences of the key that are followed by a separator like `:`, `=`, `.`
+    /// (dotted keys), `]` (TOML and INI headers) or `{` and `(` (HCL blocks and RON
+    /// structs), outside of comments
+    fn find_key<'s>(
+        &'s self,
+        range: Range<usize>,
+        anchored: bool,
+        key: &'s str,
+    ) -> impl Iterator<Item = KeyMatch> + 's {
+        let text = self.text;
+
+        text.get(range.clone())
+            .filter(|_| !key.is_empty())
+            .into_iter()
+            .flat_map(move |s| s.match_indices(key))
+            .map(move |(i, _)| range.start + i)
+            .filter_map(move |start| {
+                let before = text[..start].chars().next_back();
+                let quote = before.filter(|c| *c == '"' || *c == '\'');
+
+                let starts_token = match before {
+                    None => true,
+                    Some(c) => c.is_whitespace() || "\"'{[(,.".contains(c),
+                };
+                if !starts_token || self.in_comment(start) {
+                    return None;
+                }
+
+                let key_start = start - quote.map_or(0, char::len_utf8);
+                if anchored && key_start != range.start {
+                    return None;
+                }
+
+                let mut end = start + key.len();
+                if let Some(q) = quote {
+                    if !text[end..].starts_with(q) {
+                        return None;
+                    }
+                    end += q.len_utf8();
+                }
+
+                let rest = text[end..].trim_start_matches([' ', '\t']);
+                if !rest.starts_with([':', '=', '.', ']', '{', '(']) {
+                    return None;
+                }
+
+                Some(KeyMatch {
+                    start: key_start,
+                    end,
+                })
+            })
+    }
+
+    /// Range of the text where children of the key can be, and whether the child has to
+    /// start right at the beginning of it (for dotted keys)
+    fn value_range(&self, m: &KeyMatch, limit: usize) -> (Range<usize>, bool) {
+        let text = self.text;
+        let line_end = line_end(text.as_bytes(), m.end);
+        let sep = skip_blanks(text, m.end, line_end);
+
+        match text.as_bytes()[sep] {
+            // `a.b` dotted key, child follows the dot
+            b'.' => (sep + 1..line_end.min(limit), true),
+            // `[a]` section header, children follow until the next header
+            b']' => {
+                let end = self.next_header(line_end).unwrap_or(limit).min(limit);
+                (line_end.min(end)..end, false)
+            }
+            b'{' | b'(' => (sep..self.closing_bracket(sep).min(limit), false),
+            _ => {
+                // Bracketed value after `:` or `=`, possibly named, e.g. `Postgres(...)`
+                // in RON
+                let value = skip_blanks(text, sep + 1, line_end);
+                let name_end = text[value..line_end]
+                    .find(|c: char| !c.is_alphanumeric() && c != '_')
+                    .map_or(line_end, |i| value + i);
+                let open = skip_blanks(text, name_end, line_end);
+
+                match text.as_bytes().get(open) {
+                    Some(b'{' | b'(') => (open..self.closing_bracket(open).min(limit), false),
+                    Some(b'[') if name_end == value => {
+                        (open..self.closing_bracket(open).min(limit), false)
+                    }
+                    // Indented block, e.g. in YAML
+                    _ => (m.end..self.block_end(m.start, line_end).min(limit), false),
+                }
+            }
+        }
+    }
+
+    /// End of the lines following `line_end` that are indented deeper than the column
+    /// of the key, or start a YAML sequence at the same column
+    fn block_end(&self, key_start: usize, line_end: usize) -> usize {
+        let text = self.text;
+        let line_start = text[..key_start].rfind('\n').map_or(0, |i| i + 1);
+        let col = key_start - line_start;
+
+        let mut pos = line_end;
+        while pos < text.len() {
+            let start = pos + 1;
+            let end = text[start..].find('\n').map_or(text.len(), |i| start + i);
+            let line = &text[start..end];
+            let content = line.trim_start_matches([' ', '\t']);
+            let indent = line.len() - content.len();
+
+            let blank = content.is_empty() || self.in_comment(start + indent);
+            let nested = indent > col || (indent == col && content.starts_with('-'));
+            if !blank && !nested {
+                return pos;
+            }
+            pos = end;
+        }
+        text.len()
+    }
+
+    /// Start of the next line that begins with `[`, outside of comments
+    fn next_header(&self, from: usize) -> Option<usize> {
+        let text = self.text;
+        let mut pos = from;
+        while pos < text.len() {
+            let start = pos + 1;
+            let end = text[start..].find('\n').map_or(text.len(), |i| start + i);
+            let line = &text[start.min(end)..end];
+            let content = line.trim_start_matches([' ', '\t']);
+            let offset = start + line.len() - content.len();
+            if content.starts_with('[') && !self.in_comment(offset) {
+                return Some(start);
+            }
+            pos = end;
+        }
+        None
+    }
+
+    /// Offset after the bracket that closes the one at `open`, skipping strings and
+    /// comments
+    fn closing_bracket(&self, open: usize) -> usize {
+        let bytes = self.text.as_bytes();
+        let mut depth = 0;
+        let mut i = open;
+        while i < bytes.len() {
+            if let Some(r) = self.comments.iter().find(|r| r.contains(&i)) {
+                i = r.end;
+                continue;
+            }
+            match bytes[i] {
+                b'{' | b'[' | b'(' => depth += 1,
+                b'}' | b']' | b')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return i + 1;
+                    }
+                }
+                q @ (b'"' | b'\'') => i = string_end(bytes, i, q) - 1,
+                _ => {}
+            }
+            i += 1;
+        }
+        bytes.len()
+    }
+
+    fn in_comment(&self, offset: usize) -> bool {
+        self.comments.iter().any(|r| r.contains(&offset))
+    }
+}
+
+/// Byte ranges of comments: `#`, `//` and `/* */` after whitespace, and `;` and `!` at
+/// the start of a line (INI and `.properties`)
+fn comment_ranges(text: &str) -> Vec<Range<usize>> {
+    let bytes = text.as_bytes();
+    let mut ret = Vec::new();
+    let mut line_start = true;
+    let mut after_space = true;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        let next = bytes.get(i + 1).copied();
+
+        let comment_end = match c {
+            b'#' if after_space => Some(line_end(bytes, i)),
+            b'/' if after_space && next == Some(b'/') => Some(line_end(bytes, i)),
+            b'/' if after_space && next == Some(b'*') => Some(
+                text[i + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |j| i + 2 + j + 2),
+            ),
+            b';' | b'!' if line_start => Some(line_end(bytes, i)),
+            _ => None,
+        };
+        if let Some(end) = comment_end {
+            ret.push(i..end);
+            i = end;
+            continue;
+        }
+
+        // Skipping strings, but not apostrophes within unquoted text, e.g. `it's`
+        if (c == b'"' || c == b'\'') && (after_space || i > 0 && b":=[{(,".contains(&bytes[i - 1]))
+        {
+            i = string_end(bytes, i, c);
+            line_start = false;
+            after_space = false;
+            continue;
+        }
+
+        line_start = c == b'\n' || (line_start && c.is_ascii_whitespace());
+        after_space = c.is_ascii_whitespace();
+        i += 1;
+    }
+
+    ret
+}
+
+/// Offset after the closing quote of a string starting at `start`
+fn string_end(bytes: &[u8], start: usize, quote: u8) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if quote == b'"' => i += 1,
+            b'\n' => return i,
+            c if c == quote => return i + 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// Offset of the first character that is not a space or a tab, or `to`
+fn skip_blanks(text: &str, from: usize, to: usize) -> usize {
+    text[from..to]
+        .find(|c| c != ' ' && c != '\t')
+        .map_or(to, |i| from + i)
+}
+
+/// Offset of the end of the line containing `from`
+fn line_end(bytes: &[u8], from: usize) -> usize {
+    bytes[from..]
+        .iter()
+        .position(|c| *c == b'\n')
+        .map_or(bytes.len(), |i| from + i)
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
@@ -15,7 +15,8 @@ pub fn schema_to_markdown(schema: &schemars::Schema) -> String {
     let buf = &mut ret;
     let schema = schema.as_value();
 
-    write_type(buf, schema["title"].as_str().unwrap(), schema).unwrap();
+    let title = schema["title"].as_str().unwrap_or("Config");
+    write_type(buf, title, schema).unwrap();
 
     let empty = serde_json::Map::default();
     for (name, sch) in schema["$defs"].as_object().unwrap_or(&empty) {
@@ -28,6 +29,76 @@ pub fn schema_to_markdown(schema: &schemars::Schema) -> String {
 
 /////////////////////////////////////////////////////////////////////////////////////////
 
+/// Renders a table of environment variables returned by [`crate::Config::env_vars`]
+/// as a Markdown section that can be appended to [`schema_to_markdown`] output.
+#[cfg(feature = "gen-markdown")]
+pub fn env_vars_to_markdown(vars: &[crate::env_vars::EnvVar]) -> String {
+    let mut ret = String::new();
+    write_env_vars(&mut ret, vars).unwrap();
+    ret
+}
+
+#[cfg(feature = "gen-markdown")]
+fn write_env_vars(
+    buf: &mut String,
+    vars: &[crate::env_vars::EnvVar],
+) -> Result<(), std::fmt::Error> {
+    writeln!(buf, "## Environment Variables")?;
+    writeln!(buf)?;
+    writeln!(buf, "<table>")?;
+    writeln!(
+        buf,
+        "<thead><tr><th>Variable</th><th>Type</th><th>Default</th><th>Description</th></tr></thead>"
+    )?;
+    writeln!(buf, "<tbody>")?;
+
+    for var in vars {
+        writeln!(buf, "<tr>")?;
+
+        // Name
+        writeln!(
+            buf,
+            "<td><code>{}</code></td>",
+            html_escape::encode_safe(&var.name)
+        )?;
+
+        // Type
+        if var.values.is_empty() {
+            writeln!(
+                buf,
+                "<td><code>{}</code></td>",
+                html_escape::encode_safe(&var.typ)
+            )?;
+        } else {
+            let values: Vec<_> = var
+                .values
+                .iter()
+                .map(|v| format!("<code>{}</code>", html_escape::encode_safe(&v.to_string())))
+                .collect();
+            writeln!(buf, "<td>{}</td>", values.join(" | "))?;
+        }
+
+        // Default
+        if let Some(default) = &var.default {
+            write_default_cell(buf, default)?;
+        } else {
+            writeln!(buf, "<td></td>")?;
+        }
+
+        // Description
+        write_description_cell(buf, var.description.as_deref().unwrap_or(""))?;
+
+        writeln!(buf, "</tr>")?;
+    }
+
+    writeln!(buf, "</tbody>")?;
+    writeln!(buf, "</table>")?;
+
+    Ok(())
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
 fn write_type(buf: &mut String, name: &str, schema: &Value) -> Result<(), std::fmt::Error> {
     writeln!(buf, "## `{name}`")?;
     writeln!(buf)?;
@@ -43,14 +114,10 @@ fn write_type(buf: &mut String, name: &str, schema: &Value) -> Result<(), std::f
         write_struct(buf, name, schema)?;
     } else if schema.get("oneOf").is_some() {
         write_enum(buf, name, schema)?;
-    } else if typ == Some("string") {
-        if schema.get("enum").is_some() {
-            write_string_enum(buf, name, schema)?;
-        } else {
-            write_basic_type_wrapper(buf, name, schema)?;
-        }
+    } else if typ == Some("string") && schema.get("enum").is_some() {
+        write_string_enum(buf, name, schema)?;
     } else {
-        unreachable!("Unsupported schema in {name}:\n{schema}")
+        write_basic_type_wrapper(buf, name, schema)?;
     }
 
     Ok(())
@@ -58,7 +125,7 @@ fn write_type(buf: &mut String, name: &str, schema: &Value) -> Result<(), std::f
 
 /////////////////////////////////////////////////////////////////////////////////////////
 
-fn write_struct(buf: &mut String, name: &str, schema: &Value) -> Result<(), std::fmt::Error> {
+fn write_struct(buf: &mut String, _name: &str, schema: &Value) -> Result<(), std::fmt::Error> {
     let empty = serde_json::Map::default();
     let properties = schema
         .get("properties")
@@ -74,8 +141,8 @@ fn write_struct(buf: &mut String, name: &str, schema: &Value) -> Result<(), std:
 
     let mut required = std::collections::BTreeSet::new();
     if let Some(req) = schema.get("required").and_then(|v| v.as_array()) {
-        for v in req {
-            required.insert(v.as_str().unwrap());
+        for v in req.iter().filter_map(|v| v.as_str()) {
+            required.insert(v);
         }
     }
 
@@ -88,78 +155,24 @@ fn write_struct(buf: &mut String, name: &str, schema: &Value) -> Result<(), std:
         writeln!(buf, "<td><code>{pname}</code></td>")?;
 
         // Type
-        if let Some(ty) = psch.get("type") {
-            if let Some(ty) = ty.as_str() {
-                writeln!(buf, "<td><code>{ty}</code></td>")?;
-            } else if let Some(ty) = ty.as_array()
-                && ty.len() == 2
-            {
-                let ty = ty
-                    .iter()
-                    .filter_map(|t| t.as_str())
-                    .find(|t| *t != "null")
-                    .unwrap();
-
-                is_required = false;
-                writeln!(buf, "<td><code>{ty}</code></td>")?;
-            } else {
-                unreachable!("Unsupported schema in {name}.{pname}:\n{psch}")
-            }
-        } else if let Some(r) = psch.get("$ref").and_then(|v| v.as_str()) {
-            let (_, tname) = r.rsplit_once('/').unwrap();
-            writeln!(
-                buf,
-                "<td><a href=\"#{}\"><code>{tname}</code></a></td>",
-                name_to_id(tname)
-            )?;
-        } else if let Some(any_of) = psch.get("anyOf").and_then(|v| v.as_array()) {
-            assert_eq!(any_of.len(), 2);
-            assert_eq!(any_of[1]["type"].as_str(), Some("null"));
-            let r = any_of[0]["$ref"].as_str().unwrap();
-            let (_, tname) = r.rsplit_once('/').unwrap();
-            writeln!(
-                buf,
-                "<td><a href=\"#{}\"><code>{tname}</code></a></td>",
-                name_to_id(tname)
-            )?;
-        } else {
-            unreachable!("Unsupported schema in {name}.{pname}:\n{psch}")
-        };
+        if write_type_cell(buf, psch)? {
+            is_required = false;
+        }
 
         // Default
         let null = serde_json::Value::Null;
         if is_required {
             writeln!(buf, "<td></td>")?;
         } else {
-            let default = psch.get("default").unwrap_or(&null);
-            let default_str = serde_json::to_string_pretty(default).unwrap();
-            let escaped = html_escape::encode_safe(&default_str);
-
-            if escaped.contains('\n') {
-                writeln!(
-                    buf,
-                    "<td><pre><code class=\"language-json\">{escaped}</code></pre></td>"
-                )?;
-            } else {
-                writeln!(
-                    buf,
-                    "<td><code class=\"language-json\">{escaped}</code></td>"
-                )?;
-            }
+            write_default_cell(buf, psch.get("default").unwrap_or(&null))?;
         };
 
         // Description
         let pdesc = psch
             .get("description")
             .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .trim();
-        if pdesc.contains('\n') || pdesc.contains('`') {
-            writeln!(buf, "<td>\n\n{pdesc}\n\n</td>")?;
-        } else {
-            let pdesc = html_escape::encode_script(pdesc);
-            writeln!(buf, "<td>{pdesc}</td>")?;
-        }
+            .unwrap_or("");
+        write_description_cell(buf, pdesc)?;
 
         writeln!(buf, "</tr>")?;
     }
@@ -170,24 +183,102 @@ fn write_struct(buf: &mut String, name: &str, schema: &Value) -> Result<(), std:
     Ok(())
 }
 
+/// Writes a cell with the property type, returning whether the property is nullable
+fn write_type_cell(buf: &mut String, psch: &Value) -> Result<bool, std::fmt::Error> {
+    let (types, nullable) = type_names(psch);
+
+    if types.is_empty() {
+        writeln!(buf, "<td></td>")?;
+    } else {
+        writeln!(buf, "<td>{}</td>", types.join(" | "))?;
+    }
+
+    Ok(nullable)
+}
+
+/// Returns rendered non-null types of the property and whether it is nullable
+fn type_names(psch: &Value) -> (Vec<String>, bool) {
+    if let Some(ty) = psch.get("type") {
+        let types: Vec<_> = match ty {
+            Value::Array(types) => types.iter().filter_map(|t| t.as_str()).collect(),
+            ty => ty.as_str().into_iter().collect(),
+        };
+        let nullable = types.contains(&"null");
+        let types = types
+            .into_iter()
+            .filter(|t| *t != "null")
+            .map(|t| format!("<code>{t}</code>"))
+            .collect();
+        (types, nullable)
+    } else if let Some(r) = psch.get("$ref").and_then(|v| v.as_str()) {
+        let tname = r.rsplit_once('/').map_or(r, |(_, tname)| tname);
+        let link = format!(
+            "<a href=\"#{}\"><code>{tname}</code></a>",
+            name_to_id(tname)
+        );
+        (vec![link], false)
+    } else if let Some(any_of) = psch.get("anyOf").and_then(|v| v.as_array()) {
+        let mut types = Vec::new();
+        let mut nullable = false;
+        for variant in any_of {
+            let (vtypes, vnullable) = type_names(variant);
+            types.extend(vtypes);
+            nullable |= vnullable;
+        }
+        (types, nullable)
+    } else {
+        (Vec::new(), false)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+fn write_default_cell(buf: &mut String, default: &Value) -> Result<(), std::fmt::Error> {
+    let default_str = serde_json::to_string_pretty(default).unwrap();
+    let escaped = html_escape::encode_safe(&default_str);
+
+    if escaped.contains('\n') {
+        writeln!(
+            buf,
+            "<td><pre><code class=\"language-json\">{escaped}</code></pre></td>"
+        )
+    } else {
+        writeln!(
+            buf,
+            "<td><code class=\"language-json\">{escaped}</code></td>"
+        )
+    }
+}
+
+fn write_description_cell(buf: &mut String, desc: &str) -> Result<(), std::fmt::Error> {
+    let desc = desc.trim();
+    if desc.contains('\n') || desc.contains('`') {
+        writeln!(buf, "<td>\n\n{desc}\n\n</td>")
+    } else {
+        let desc = html_escape::encode_script(desc);
+        writeln!(buf, "<td>{desc}</td>")
+    }
+}
+
 /////////////////////////////////////////////////////////////////////////////////////////
 
 fn write_enum(buf: &mut String, name: &str, schema: &Value) -> Result<(), std::fmt::Error> {
     let Some(variants) = schema.get("oneOf").and_then(|p| p.as_array()) else {
-        unreachable!()
+        return Ok(());
     };
 
     let tag_property = crate::merge_with_defaults::get_enum_tag_property_name(variants);
+    let tags: Vec<_> = variants
+        .iter()
+        .enumerate()
+        .map(|(i, v)| variant_name(v, tag_property.map(String::as_str)).unwrap_or(i.to_string()))
+        .collect();
 
     writeln!(buf, "<table>")?;
     writeln!(buf, "<thead><tr><th>Variants</th></tr></thead>")?;
     writeln!(buf, "<tbody>")?;
 
-    for variant in variants.iter() {
-        let tag = variant["properties"][tag_property]["const"]
-            .as_str()
-            .unwrap();
-
+    for tag in &tags {
         let vname = format!("{name}::{tag}");
 
         writeln!(
@@ -200,11 +291,10 @@ fn write_enum(buf: &mut String, name: &str, schema: &Value) -> Result<(), std::f
     writeln!(buf, "</tbody>")?;
     writeln!(buf, "</table>")?;
 
-    for vsch in variants.iter() {
+    for (vsch, tag) in variants.iter().zip(&tags) {
         writeln!(buf)?;
         writeln!(buf)?;
 
-        let tag = vsch["properties"][tag_property]["const"].as_str().unwrap();
         let vname = format!("{name}::{tag}");
         write_type(buf, &vname, vsch)?;
     }
@@ -212,6 +302,22 @@ fn write_enum(buf: &mut String, name: &str, schema: &Value) -> Result<(), std::f
     Ok(())
 }
 
+/// Name of the variant from the tag property, a constant, or the only key of an
+/// externally tagged variant
+fn variant_name(variant: &Value, tag_property: Option<&str>) -> Option<String> {
+    let name = match tag_property {
+        Some(tag) => &variant["properties"][tag]["const"],
+        None => match variant.get("const") {
+            Some(c) => c,
+            None => match variant["required"].as_array().map(Vec::as_slice) {
+                Some([name]) => name,
+                _ => &Value::Null,
+            },
+        },
+    };
+    name.as_str().map(str::to_string)
+}
+
 /////////////////////////////////////////////////////////////////////////////////////////
 
 fn write_string_enum(buf: &mut String, _name: &str, schema: &Value) -> Result<(), std::fmt::Error> {
@@ -219,12 +325,20 @@ fn write_string_enum(buf: &mut String, _name: &str, schema: &Value) -> Result<()
     writeln!(buf, "<thead><tr><th>Variants</th></tr></thead>")?;
     writeln!(buf, "<tbody>")?;
 
-    for variant in schema.get("enum").and_then(|v| v.as_array()).unwrap() {
-        let tag = variant.as_str().unwrap();
+    let empty = Vec::new();
+    for variant in schema
+        .get("enum")
+        .and_then(|v| v.as_array())
+        .unwrap_or(&empty)
+    {
+        let tag = match variant {
+            Value::String(s) => s.clone(),
+            v => v.to_string(),
+        };
         writeln!(
             buf,
             "<tr><td><code>{}</code></td></tr>",
-            html_escape::encode_safe(tag)
+            html_escape::encode_safe(&tag)
         )?;
     }
 
@@ -241,8 +355,9 @@ fn write_basic_type_wrapper(
     _name: &str,
     schema: &Value,
 ) -> Result<(), std::fmt::Error> {
-    let typ = schema.get("type").and_then(|p| p.as_str()).unwrap();
-    writeln!(buf, "Base type: `{typ}`")?;
+    if let Some(typ) = schema.get("type").and_then(|p| p.as_str()) {
+        writeln!(buf, "Base type: `{typ}`")?;
+    }
     Ok(())
 }
 
@@ -2,19 +2,25 @@
 
 use crate::Value;
 
+pub(crate) use crate::schema_helpers::get_enum_tag_property_name;
+
 /////////////////////////////////////////////////////////////////////////////////////////
 
 pub fn merge_with_defaults(name: &str, value: &mut Value, sch: &Value, defs: &Value) {
     if let Some(r) = sch.get("$ref").and_then(|v| v.as_str()) {
-        let (_, tname) = r.rsplit_once('/').unwrap();
-        let rsch = &defs[tname];
+        let Some(rsch) = r.rsplit_once('/').and_then(|(_, tname)| defs.get(tname)) else {
+            return;
+        };
         return merge_with_defaults(name, value, rsch, defs);
     }
     if let Some(any_of) = sch.get("anyOf").and_then(|v| v.as_array()) {
         // `anyOf` only appears on nullable types
-        assert_eq!(any_of.len(), 2);
-        assert_eq!(any_of[1]["type"].as_str(), Some("null"));
-        let rsch = &any_of[0];
+        let Some(rsch) = any_of
+            .iter()
+            .find(|v| v.get("type").and_then(|t| t.as_str()) != Some("null"))
+        else {
+            return;
+        };
         return merge_with_defaults(name, value, rsch, defs);
     }
 
@@ -22,7 +28,6 @@ pub fn merge_with_defaults(name: &str, value: &mut Value, sch: &Value, defs: &Va
     let typ = if let Some(typ) = typ.and_then(|t| t.as_str()) {
         Some(typ)
     } else if let Some(typ) = typ.and_then(|t| t.as_array()) {
-        assert_eq!(typ.len(), 2);
         typ.iter().filter_map(|t| t.as_str()).find(|t| *t != "null")
     } else {
         None
@@ -37,19 +42,25 @@ pub fn merge_with_defaults(name: &str, value: &mut Value, sch: &Value, defs: &Va
     } else if typ.is_some() {
         merge_basic_type_wrapper(name, value, sch, defs);
     } else {
-        unreachable!("Unsupported schema in `{name}`:\n{sch}")
+        // Unsupported schema - leaving the value as is
     }
 }
 
 /////////////////////////////////////////////////////////////////////////////////////////
 
 fn merge_struct(_name: &str, value: &mut Value, sch: &Value, defs: &Value) {
-    let value = value.as_object_mut().unwrap();
+    // Value of a wrong type will be reported when deserializing
+    let Some(value) = value.as_object_mut() else {
+        return;
+    };
 
-    let empty = Value::Object(Default::default());
-    let props = sch.get("properties").unwrap_or(&empty);
+    let empty = serde_json::Map::new();
+    let props = sch
+        .get("properties")
+        .and_then(|p| p.as_object())
+        .unwrap_or(&empty);
 
-    for (pname, psch) in props.as_object().unwrap() {
+    for (pname, psch) in props {
         if let Some(pvalue) = value.get_mut(pname) {
             merge_with_defaults(pname, pvalue, psch, defs);
         } else if let Some(default) = psch.get("default") {
@@ -65,9 +76,14 @@ fn merge_struct(_name: &str, value: &mut Value, sch: &Value, defs: &Value) {
 /////////////////////////////////////////////////////////////////////////////////////////
 
 fn merge_enum(name: &str, value: &mut Value, sch: &Value, defs: &Value) {
-    let variants = sch.get("oneOf").and_then(|p| p.as_array()).unwrap();
+    let Some(variants) = sch.get("oneOf").and_then(|p| p.as_array()) else {
+        return;
+    };
 
-    let tag_property = crate::merge_with_defaults::get_enum_tag_property_name(variants);
+    let Some(tag_property) = get_enum_tag_property_name(variants) else {
+        // Not an internally tagged enum
+        return;
+    };
 
     let Some(tag) = value.get(tag_property) else {
         // No tag - can't determine the right variant
@@ -94,17 +110,3 @@ fn merge_string_enum(_name: &str, _value: &mut Value, _sch: &Value, _defs: &Valu
 fn merge_basic_type_wrapper(_name: &str, _value: &mut Value, _sch: &Value, _defs: &Value) {}
 
 /////////////////////////////////////////////////////////////////////////////////////////
-
-pub(crate) fn get_enum_tag_property_name(variants: &[Value]) -> &String {
-    variants[0].as_object().unwrap()["properties"]
-        .as_object()
-        .unwrap()
-        .iter()
-        .filter(|(_name, prop)| prop.get("type").and_then(|t| t.as_str()) == Some("string"))
-        .filter(|(_name, prop)| prop.get("const").and_then(|t| t.as_str()).is_some())
-        .map(|(name, __)| name)
-        .next()
-        .unwrap()
-}
-
-/////////////////////////////////////////////////////////////////////////////////////////
@@ -0,0 +1,449 @@
+use crate::Value;
+use crate::errors::PathError;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Path of a value in the config, e.g. `servers[0].host`,
+/// `labels."app.kubernetes.io/name"` or `clusters.*.timeout`.
+///
+/// Syntax:
+/// - keys are separated by `.`
+/// - keys that are empty, equal to `*` or contain `.`, `[`, `]`, `"`, `=` or whitespace
+///   are written in double quotes, with `\"` and `\\` escapes
+/// - `[N]` selects an element of an array
+/// - `*` or `[*]` selects all entries of an object or all elements of an array
+///
+/// Empty path refers to the root value.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct KeyPath {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Segment {
+    Key(String),
+    Index(usize),
+    Wildcard,
+}
+
+impl KeyPath {
+    /// Returns path of the root value
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn parse(path: &str) -> Result<Self, PathError> {
+        Parser { path, pos: 0 }.parse()
+    }
+
+    /// Parses a `path=value` pair, e.g. a command line override, splitting at the first
+    /// `=` outside of quotes
+    pub fn parse_assignment(assignment: &str) -> Result<(Self, &str), PathError> {
+        let mut quoted = false;
+        let mut escaped = false;
+
+        for (i, c) in assignment.char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' if quoted => escaped = true,
+                '"' => quoted = !quoted,
+                '=' if !quoted => {
+                    return Ok((Self::parse(&assignment[..i])?, &assignment[i + 1..]));
+                }
+                _ => {}
+            }
+        }
+
+        Err(PathError::Syntax {
+            path: assignment.to_string(),
+            position: assignment.len(),
+            reason: "expected `=`",
+        })
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    pub fn push(&mut self, segment: Segment) {
+        self.segments.push(segment);
+    }
+
+    pub fn pop(&mut self) -> Option<Segment> {
+        self.segments.pop()
+    }
+
+    /// Returns `true` if path contains only keys
+    pub fn is_keys_only(&self) -> bool {
+        self.segments.iter().all(|s| matches!(s, Segment::Key(_)))
+    }
+
+    /// Returns `true` if path can match multiple values
+    pub fn has_wildcards(&self) -> bool {
+        self.segments.contains(&Segment::Wildcard)
+    }
+
+    /// Returns the value under the path.
+    ///
+    /// Wildcards keep the shape of the data, e.g. `clusters.*.timeout` returns an object
+    /// with timeouts of all clusters that have them.
+    pub fn select(&self, value: &Value) -> Option<Value> {
+        select_rec(&self.segments, value)
+    }
+
+    /// Sets the value under the path, creating missing objects along the way.
+    ///
+    /// Indices must point to existing elements, and wildcards must match at least one
+    /// value.
+    pub fn set(&self, target: &mut Value, value: Value) -> Result<(), PathError> {
+        self.update(target, |_, v| {
+            *v = value.clone();
+            Ok(())
+        })
+    }
+
+    /// Calls `f` for every value under the path with the path of that value, where
+    /// wildcards are replaced with the matched keys and indices. Missing objects along
+    /// the way are created, so `f` receives `null` for a missing value.
+    ///
+    /// Indices must point to existing elements, and wildcards must match at least one
+    /// value.
+    pub fn update(
+        &self,
+        target: &mut Value,
+        mut f: impl FnMut(&KeyPath, &mut Value) -> Result<(), PathError>,
+    ) -> Result<(), PathError> {
+        self.update_rec(&mut KeyPath::new(), target, &mut f)
+    }
+
+    /// Removes and returns the value under the path.
+    ///
+    /// Wildcards return the removed values in the shape of the data, same as
+    /// [`KeyPath::select()`].
+    pub fn remove(&self, target: &mut Value) -> Option<Value> {
+        remove_rec(&self.segments, target)
+    }
+
+    fn update_rec(
+        &self,
+        current: &mut KeyPath,
+        target: &mut Value,
+        f: &mut dyn FnMut(&KeyPath, &mut Value) -> Result<(), PathError>,
+    ) -> Result<(), PathError> {
+        let Some(segment) = self.segments.get(current.segments.len()) else {
+            return f(current, target);
+        };
+
+        match segment {
+            Segment::Key(key) => {
+                if target.is_null() {
+                    *target = Value::Object(Default::default());
+                }
+                let Value::Object(map) = target else {
+                    return Err(PathError::invalid_type(current, "object", target));
+                };
+                let child = map.entry(key.clone()).or_insert(Value::Null);
+                current.push(Segment::Key(key.clone()));
+                let res = self.update_rec(current, child, f);
+                current.pop();
+                res
+            }
+            Segment::Index(index) => {
+                let Value::Array(items) = target else {
+                    return Err(PathError::invalid_type(current, "array", target));
+                };
+                let len = items.len();
+                let Some(item) = items.get_mut(*index) else {
+                    return Err(PathError::IndexOutOfBounds {
+                        path: current.to_string(),
+                        index: *index,
+                        len,
+                    });
+                };
+                current.push(Segment::Index(*index));
+                let res = self.update_rec(current, item, f);
+                current.pop();
+                res
+            }
+            Segment::Wildcard => {
+                let items: Vec<(Segment, &mut Value)> = match target {
+                    Value::Object(map) => map
+                        .iter_mut()
+                        .map(|(k, v)| (Segment::Key(k.clone()), v))
+                        .collect(),
+                    Value::Array(items) => items
+                        .iter_mut()
+                        .enumerate()
+                        .map(|(i, v)| (Segment::Index(i), v))
+                        .collect(),
+                    _ => {
+                        return Err(PathError::invalid_type(current, "object or array", target));
+                    }
+                };
+                if items.is_empty() {
+                    return Err(PathError::NoMatch {
+                        path: self.to_string(),
+                    });
+                }
+                for (segment, item) in items {
+                    current.push(segment);
+                    let res = self.update_rec(current, item, f);
+                    current.pop();
+                    res?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+impl std::str::FromStr for KeyPath {
+    type Err = PathError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl FromIterator<Segment> for KeyPath {
+    fn from_iter<T: IntoIterator<Item = Segment>>(iter: T) -> Self {
+        Self {
+            segments: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl std::fmt::Display for KeyPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                Segment::Index(index) => write!(f, "[{index}]")?,
+                Segment::Key(_) | Segment::Wildcard if i != 0 => f.write_str(".")?,
+                _ => {}
+            }
+            match segment {
+                Segment::Key(key) if needs_quotes(key) => {
+                    write!(f, "\"{}\"", key.replace('\\', "\\\\").replace('"', "\\\""))?
+                }
+                Segment::Key(key) => f.write_str(key)?,
+                Segment::Wildcard => f.write_str("*")?,
+                Segment::Index(_) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+fn needs_quotes(key: &str) -> bool {
+    key.is_empty() || key == "*" || key.chars().any(is_special)
+}
+
+fn is_special(c: char) -> bool {
+    matches!(c, '.' | '[' | ']' | '"' | '=') || c.is_whitespace()
+}
+
+impl PathError {
+    /// Value at `path` has a type that can't be used, e.g. in [`KeyPath::update()`]
+    pub fn invalid_type(path: &KeyPath, expected: &'static str, found: &Value) -> Self {
+        Self::InvalidType {
+            path: path.to_string(),
+            expected,
+            found: type_name(found),
+        }
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+struct Parser<'a> {
+    path: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn parse(mut self) -> Result<KeyPath, PathError> {
+        let mut segments = Vec::new();
+
+        if self.path.is_empty() {
+            return Ok(KeyPath { segments });
+        }
+
+        // Root arrays can be indexed right away, e.g. `[0].name`
+        if !self.path.starts_with('[') {
+            segments.push(self.key()?);
+        }
+
+        while let Some(c) = self.peek() {
+            match c {
+                '.' => {
+                    self.pos += 1;
+                    segments.push(self.key()?);
+                }
+                '[' => {
+                    self.pos += 1;
+                    segments.push(self.index()?);
+                }
+                _ => return Err(self.error(self.pos, "expected `.` or `[`")),
+            }
+        }
+
+        Ok(KeyPath { segments })
+    }
+
+    fn key(&mut self) -> Result<Segment, PathError> {
+        if self.peek() == Some('"') {
+            return self.quoted_key();
+        }
+
+        let rest = &self.path[self.pos..];
+        let len = rest.find(is_special).unwrap_or(rest.len());
+        let key = &rest[..len];
+
+        if key.is_empty() {
+            return Err(self.error(self.pos, "expected a key"));
+        }
+        self.pos += len;
+
+        if key == "*" {
+            Ok(Segment::Wildcard)
+        } else {
+            Ok(Segment::Key(key.to_string()))
+        }
+    }
+
+    fn quoted_key(&mut self) -> Result<Segment, PathError> {
+        let start = self.pos;
+        self.pos += 1;
+
+        let mut key = String::new();
+        while let Some(c) = self.peek() {
+            self.pos += c.len_utf8();
+            match c {
+                '"' => return Ok(Segment::Key(key)),
+                '\\' => match self.peek() {
+                    Some(c @ ('"' | '\\')) => {
+                        self.pos += 1;
+                        key.push(c);
+                    }
+                    _ => return Err(self.error(self.pos - 1, "invalid escape")),
+                },
+                c => key.push(c),
+            }
+        }
+
+        Err(self.error(start, "unterminated quotes"))
+    }
+
+    fn index(&mut self) -> Result<Segment, PathError> {
+        let start = self.pos;
+        let rest = &self.path[start..];
+        let Some(len) = rest.find(']') else {
+            return Err(self.error(start - 1, "unclosed `[`"));
+        };
+        self.pos += len + 1;
+
+        match &rest[..len] {
+            "*" => Ok(Segment::Wildcard),
+            index => index
+                .parse()
+                .map(Segment::Index)
+                .map_err(|_| self.error(start, "expected an index or `*`")),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.path[self.pos..].chars().next()
+    }
+
+    fn error(&self, position: usize, reason: &'static str) -> PathError {
+        PathError::Syntax {
+            path: self.path.to_string(),
+            position,
+            reason,
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+fn select_rec(segments: &[Segment], value: &Value) -> Option<Value> {
+    let Some((segment, rest)) = segments.split_first() else {
+        return Some(value.clone());
+    };
+
+    match segment {
+        Segment::Key(key) => select_rec(rest, value.as_object()?.get(key)?),
+        Segment::Index(index) => select_rec(rest, value.as_array()?.get(*index)?),
+        Segment::Wildcard => match value {
+            Value::Object(map) => Some(Value::Object(
+                map.iter()
+                    .filter_map(|(k, v)| Some((k.clone(), select_rec(rest, v)?)))
+                    .collect(),
+            )),
+            Value::Array(items) => Some(Value::Array(
+                items.iter().filter_map(|v| select_rec(rest, v)).collect(),
+            )),
+            _ => None,
+        },
+    }
+}
+
+fn remove_rec(segments: &[Segment], target: &mut Value) -> Option<Value> {
+    let (segment, rest) = segments.split_first()?;
+
+    if !rest.is_empty() {
+        return match segment {
+            Segment::Key(key) => remove_rec(rest, target.as_object_mut()?.get_mut(key)?),
+            Segment::Index(index) => remove_rec(rest, target.as_array_mut()?.get_mut(*index)?),
+            Segment::Wildcard => match target {
+                Value::Object(map) => {
+                    let removed: serde_json::Map<_, _> = map
+                        .iter_mut()
+                        .filter_map(|(k, v)| Some((k.clone(), remove_rec(rest, v)?)))
+                        .collect();
+                    (!removed.is_empty()).then_some(Value::Object(removed))
+                }
+                Value::Array(items) => {
+                    let removed: Vec<_> = items
+                        .iter_mut()
+                        .filter_map(|v| remove_rec(rest, v))
+                        .collect();
+                    (!removed.is_empty()).then_some(Value::Array(removed))
+                }
+                _ => None,
+            },
+        };
+    }
+
+    match segment {
+        // Plain `remove()` swaps the last key into the place of the removed one
+        #[cfg(feature = "preserve-order")]
+        Segment::Key(key) => target.as_object_mut()?.shift_remove(key),
+        #[cfg(not(feature = "preserve-order"))]
+        Segment::Key(key) => target.as_object_mut()?.remove(key),
+        Segment::Index(index) => {
+            let items = target.as_array_mut()?;
+            (*index < items.len()).then(|| items.remove(*index))
+        }
+        Segment::Wildcard => match target {
+            Value::Object(map) if !map.is_empty() => Some(Value::Object(std::mem::take(map))),
+            Value::Array(items) if !items.is_empty() => Some(Value::Array(std::mem::take(items))),
+            _ => None,
+        },
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
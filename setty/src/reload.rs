@@ -0,0 +1,153 @@
+#![cfg(feature = "derive-jsonschema")]
+
+use crate::Value;
+use crate::merge_with_defaults::get_enum_tag_property_name;
+use crate::path::{KeyPath, Segment};
+use crate::schema_helpers;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Whether a change of a config field can be applied at runtime, set via
+/// `#[config(reload = "hot" | "restart")]`.
+///
+/// Fields without the attribute inherit the policy of the closest annotated parent
+/// and are [`ReloadPolicy::Hot`] by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReloadPolicy {
+    #[default]
+    Hot,
+    Restart,
+}
+
+impl ReloadPolicy {
+    fn from_schema(sch: &Value) -> Option<Self> {
+        match sch.get("reload")?.as_str()? {
+            "hot" => Some(Self::Hot),
+            "restart" => Some(Self::Restart),
+            _ => None,
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Paths of values that differ between two configs, split by their
+/// [`ReloadPolicy`], e.g. to log `restart required for server.port` when config
+/// is reloaded (see [`crate::Config::watch()`]).
+///
+/// Paths are rendered as [`KeyPath`]s, e.g. `labels."app.kubernetes.io/name"`, sorted,
+/// and arrays are compared as a whole.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReloadPlan {
+    /// Changes that can be applied at runtime
+    pub hot: Vec<String>,
+    /// Changes that require restart
+    pub restart: Vec<String>,
+}
+
+impl ReloadPlan {
+    /// Compares two config values, e.g. returned by [`crate::Config::data()`]
+    pub fn new(schema: &crate::schema::Schema, old: &Value, new: &Value) -> Self {
+        let schema = schema.as_value();
+        let null = Value::Null;
+        let defs = schema.get("$defs").unwrap_or(&null);
+
+        let mut plan = Self::default();
+        let mut path = KeyPath::new();
+        plan.diff(
+            &mut path,
+            Some(old),
+            Some(new),
+            schema,
+            defs,
+            ReloadPolicy::Hot,
+        );
+        plan.hot.sort();
+        plan.restart.sort();
+        plan
+    }
+
+    /// Compares two typed configs
+    #[cfg(feature = "derive-serialize")]
+    pub fn between<Cfg>(old: &Cfg, new: &Cfg) -> Result<Self, crate::errors::ReadError>
+    where
+        Cfg: serde::Serialize + schemars::JsonSchema,
+    {
+        let to_value =
+            |cfg| serde_json::to_value(cfg).map_err(|e| crate::errors::ReadError::Serde(e.into()));
+        let schema = schemars::schema_for!(Cfg).into();
+        Ok(Self::new(&schema, &to_value(old)?, &to_value(new)?))
+    }
+
+    /// Returns `true` if nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.hot.is_empty() && self.restart.is_empty()
+    }
+
+    /// Returns `true` if some of the changes can't be applied at runtime
+    pub fn requires_restart(&self) -> bool {
+        !self.restart.is_empty()
+    }
+
+    fn diff(
+        &mut self,
+        path: &mut KeyPath,
+        old: Option<&Value>,
+        new: Option<&Value>,
+        sch: &Value,
+        defs: &Value,
+        policy: ReloadPolicy,
+    ) {
+        if old == new {
+            return;
+        }
+
+        let policy = ReloadPolicy::from_schema(sch).unwrap_or(policy);
+        let sch = resolve(sch, old.or(new).unwrap_or(&Value::Null), defs);
+
+        let (Some(Value::Object(old)), Some(Value::Object(new))) = (old, new) else {
+            let path = path.to_string();
+            match policy {
+                ReloadPolicy::Hot => self.hot.push(path),
+                ReloadPolicy::Restart => self.restart.push(path),
+            }
+            return;
+        };
+
+        let keys = old
+            .keys()
+            .chain(new.keys().filter(|k| !old.contains_key(*k)));
+
+        for key in keys {
+            let psch = sch
+                .get("properties")
+                .and_then(|p| p.get(key))
+                .or_else(|| sch.get("additionalProperties"))
+                .unwrap_or(&Value::Null);
+
+            path.push(Segment::Key(key.clone()));
+            self.diff(path, old.get(key), new.get(key), psch, defs, policy);
+            path.pop();
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Follows `$ref`s, unwraps nullable `anyOf`s and picks the variant of internally
+/// tagged enums that corresponds to the value
+fn resolve<'a>(sch: &'a Value, value: &Value, defs: &'a Value) -> &'a Value {
+    let sch = schema_helpers::resolve(sch, defs);
+    if let Some(variants) = sch.get("oneOf").and_then(|v| v.as_array())
+        && let Some(tag_property) = get_enum_tag_property_name(variants)
+        && let Some(tag) = value.get(tag_property)
+        && let Some(vsch) = variants
+            .iter()
+            .find(|v| v["properties"][tag_property]["const"] == *tag)
+    {
+        return schema_helpers::resolve(vsch, defs);
+    }
+    sch
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
@@ -0,0 +1,38 @@
+use crate::Value;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Follows `$ref`s and unwraps nullable `anyOf`s
+pub(crate) fn resolve<'a>(sch: &'a Value, defs: &'a Value) -> &'a Value {
+    if let Some(r) = sch.get("$ref").and_then(|v| v.as_str())
+        && let Some((_, tname)) = r.rsplit_once('/')
+        && let Some(rsch) = defs.get(tname)
+    {
+        return resolve(rsch, defs);
+    }
+    if let Some(any_of) = sch.get("anyOf").and_then(|v| v.as_array())
+        && let Some(rsch) = any_of
+            .iter()
+            .find(|v| v.get("type").and_then(|t| t.as_str()) != Some("null"))
+    {
+        return resolve(rsch, defs);
+    }
+    sch
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Name of the string `const` property shared by the variants of internally tagged enums
+pub(crate) fn get_enum_tag_property_name(variants: &[Value]) -> Option<&String> {
+    variants
+        .first()?
+        .get("properties")?
+        .as_object()?
+        .iter()
+        .filter(|(_name, prop)| prop.get("type").and_then(|t| t.as_str()) == Some("string"))
+        .filter(|(_name, prop)| prop.get("const").and_then(|t| t.as_str()).is_some())
+        .map(|(name, __)| name)
+        .next()
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
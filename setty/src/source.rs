@@ -1,8 +1,9 @@
-use std::{borrow::Cow, marker::PhantomData, path::PathBuf};
+use std::{borrow::Cow, marker::PhantomData, path::PathBuf, sync::Arc};
 
 use crate::Value;
-use crate::errors::ReadError;
-use crate::format::Format;
+use crate::errors::{Location, PathError, ReadError};
+use crate::format::{Conversion, DynFormat, Format, FormatAdapter};
+use crate::path::{KeyPath, Segment};
 
 /////////////////////////////////////////////////////////////////////////////////////////
 
@@ -17,6 +18,137 @@ pub trait Source {
     /// Load the source and return `Ok(Some(value))` if present, `Ok(None)`
     /// if the source is absent, or `Err(ReadError)` on error.
     fn load(&self) -> Result<Option<Value>, ReadError>;
+
+    /// Load the source as a sequence of named layers that are merged in order,
+    /// e.g. one per document of a multi-document YAML file.
+    ///
+    /// Defaults to a single layer returned by [`Source::load()`].
+    fn load_layers(&self) -> Result<Vec<Layer>, ReadError> {
+        Ok(self
+            .load()?
+            .map(|value| Layer {
+                name: self.name(),
+                value,
+                text: None,
+                format: None,
+                conversion: Conversion::None,
+            })
+            .into_iter()
+            .collect())
+    }
+
+    /// Files that this source reads, used to reload the config when they change
+    /// (see [`crate::Config::watch()`]). The file the data is read from comes first,
+    /// followed by the files it refers to, e.g. via `!include` tags of `YamlWithTags`.
+    ///
+    /// Defaults to no files.
+    fn watch_paths(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Data loaded from a [`Source`], see [`Source::load_layers()`]
+#[derive(Debug, Clone)]
+pub struct Layer {
+    /// Name used in diagnostics, e.g. file path
+    pub name: Cow<'static, str>,
+    pub value: Value,
+    /// Original text the value was parsed from, used to point at the offending
+    /// lines when merged config fails to deserialize
+    pub text: Option<Arc<str>>,
+    /// Format the text was parsed with, used to find the offending keys by positions
+    /// reported by the parser (see [`Format::key_position()`])
+    pub format: Option<Arc<dyn DynFormat>>,
+    /// Conversion the value needs to match the config types, see [`Format::conversion()`]
+    pub conversion: Conversion,
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Names layers of a multi-document source as `name#1`, `name#2`, etc. skipping
+/// empty documents, while single document is used as is
+fn documents_to_layers(
+    name: Cow<'static, str>,
+    format: &Arc<dyn DynFormat>,
+    text: &str,
+    docs: Vec<Value>,
+) -> Vec<Layer> {
+    let conversion = format.conversion();
+    let text: Arc<str> = text.into();
+
+    if docs.len() == 1 {
+        return docs
+            .into_iter()
+            .map(|value| Layer {
+                name: name.clone(),
+                value,
+                text: Some(text.clone()),
+                format: Some(format.clone()),
+                conversion,
+            })
+            .collect();
+    }
+
+    docs.into_iter()
+        .enumerate()
+        .filter(|(_, v)| !v.is_null())
+        .map(|(i, value)| Layer {
+            name: format!("{name}#{}", i + 1).into(),
+            value,
+            text: Some(text.clone()),
+            format: Some(format.clone()),
+            conversion,
+        })
+        .collect()
+}
+
+/// Parses a single value of [`Env`] or [`Overrides`].
+///
+/// Formats without types (see [`Conversion::Types`]) can only parse whole documents
+/// of keys, so their values are taken as strings to be converted by the schema.
+fn parse_value(format: &dyn DynFormat, value: &str) -> Result<Value, ReadError> {
+    if format.conversion() == Conversion::Types {
+        return Ok(Value::String(value.to_string()));
+    }
+    format.deserialize(value).map_err(ReadError::Serde)
+}
+
+/// Single layer of values parsed by [`parse_value()`] that need the conversion of
+/// the format
+fn value_layer(
+    name: Cow<'static, str>,
+    format: &dyn DynFormat,
+    value: Option<Value>,
+) -> Vec<Layer> {
+    value
+        .map(|value| Layer {
+            name,
+            value,
+            text: None,
+            format: None,
+            conversion: format.conversion(),
+        })
+        .into_iter()
+        .collect()
+}
+
+/// Attaches source name and position to an error returned by the format
+fn syntax_error(
+    name: Cow<'static, str>,
+    format: &dyn DynFormat,
+    text: &str,
+    error: Box<dyn std::error::Error + Send + Sync>,
+) -> ReadError {
+    let location = match format.error_position(error.as_ref(), text) {
+        Some((line, col)) => Location::at(name, text, line, col),
+        None => Location::new(name),
+    };
+    ReadError::Syntax {
+        location,
+        source: error,
+    }
 }
 
 /////////////////////////////////////////////////////////////////////////////////////////
@@ -36,15 +168,20 @@ impl Source for Value {
 /////////////////////////////////////////////////////////////////////////////////////////
 
 /// Allows passing a raw string of data as a [`Source`]
-pub struct RawData<Fmt> {
+///
+/// Use [`RawData::new()`] with a static [`Format`] or [`RawData::with_format()`] with
+/// the format chosen at runtime.
+pub struct RawData<Fmt: ?Sized = dyn DynFormat> {
     val: String,
-    _p: PhantomData<Fmt>,
+    format: Arc<dyn DynFormat>,
+    _p: PhantomData<fn() -> Box<Fmt>>,
 }
 
-impl<Fmt> Clone for RawData<Fmt> {
+impl<Fmt: ?Sized> Clone for RawData<Fmt> {
     fn clone(&self) -> Self {
         Self {
             val: self.val.clone(),
+            format: self.format.clone(),
             _p: PhantomData,
         }
     }
@@ -52,28 +189,52 @@ impl<Fmt> Clone for RawData<Fmt> {
 
 impl<Fmt> RawData<Fmt>
 where
-    Fmt: Format,
+    Fmt: Format + 'static,
 {
     pub fn new(val: impl Into<String>) -> Self {
         Self {
             val: val.into(),
+            format: Arc::new(FormatAdapter::<Fmt>::new()),
             _p: PhantomData,
         }
     }
 }
 
-impl<Fmt> Source for RawData<Fmt>
-where
-    Fmt: Format,
-{
+impl RawData {
+    pub fn with_format(val: impl Into<String>, format: impl Into<Arc<dyn DynFormat>>) -> Self {
+        Self {
+            val: val.into(),
+            format: format.into(),
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<Fmt: ?Sized> Source for RawData<Fmt> {
     fn name(&self) -> std::borrow::Cow<'static, str> {
-        format!("<raw {} data>", Fmt::name()).into()
+        format!("<raw {} data>", self.format.name()).into()
     }
 
     fn load(&self) -> Result<Option<Value>, ReadError> {
-        let v = Fmt::deserialize(&self.val).map_err(|e| ReadError::Serde(e.into()))?;
+        let v = self
+            .format
+            .deserialize(&self.val)
+            .map_err(|e| syntax_error(self.name(), self.format.as_ref(), &self.val, e))?;
         Ok(Some(v))
     }
+
+    fn load_layers(&self) -> Result<Vec<Layer>, ReadError> {
+        let docs = self
+            .format
+            .deserialize_documents(&self.val)
+            .map_err(|e| syntax_error(self.name(), self.format.as_ref(), &self.val, e))?;
+        Ok(documents_to_layers(
+            self.name(),
+            &self.format,
+            &self.val,
+            docs,
+        ))
+    }
 }
 
 /////////////////////////////////////////////////////////////////////////////////////////
@@ -82,17 +243,22 @@ where
 ///
 /// By default `File::new(path)` is required (missing file causes an IO error).
 /// Use `required(false)` to make the file optional.
-pub struct File<Fmt> {
+///
+/// Use [`File::with_format()`] to read a file in a format chosen at runtime,
+/// e.g. via [`crate::format::FormatRegistry`].
+pub struct File<Fmt: ?Sized = dyn DynFormat> {
     path: PathBuf,
     required: bool,
-    _p: PhantomData<Fmt>,
+    format: Arc<dyn DynFormat>,
+    _p: PhantomData<fn() -> Box<Fmt>>,
 }
 
-impl<Fmt> Clone for File<Fmt> {
+impl<Fmt: ?Sized> Clone for File<Fmt> {
     fn clone(&self) -> Self {
         Self {
             path: self.path.clone(),
             required: self.required,
+            format: self.format.clone(),
             _p: PhantomData,
         }
     }
@@ -100,26 +266,37 @@ impl<Fmt> Clone for File<Fmt> {
 
 impl<Fmt> File<Fmt>
 where
-    Fmt: Format,
+    Fmt: Format + 'static,
 {
     pub fn new(path: impl Into<PathBuf>) -> Self {
         Self {
             path: path.into(),
             required: true,
+            format: Arc::new(FormatAdapter::<Fmt>::new()),
             _p: PhantomData,
         }
     }
+}
 
+impl File {
+    pub fn with_format(path: impl Into<PathBuf>, format: impl Into<Arc<dyn DynFormat>>) -> Self {
+        Self {
+            path: path.into(),
+            required: true,
+            format: format.into(),
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<Fmt: ?Sized> File<Fmt> {
     pub fn required(mut self, required: bool) -> Self {
         self.required = required;
         self
     }
 }
 
-impl<Fmt> Source for File<Fmt>
-where
-    Fmt: Format,
-{
+impl<Fmt: ?Sized> Source for File<Fmt> {
     fn name(&self) -> std::borrow::Cow<'static, str> {
         self.path.display().to_string().into()
     }
@@ -130,11 +307,35 @@ where
         }
 
         // TODO: Use reader
-        // TODO: Carry file name info
         let s = std::fs::read_to_string(&self.path)?;
-        let v = Fmt::deserialize(&s).map_err(|e| ReadError::Serde(e.into()))?;
+        let v = self
+            .format
+            .deserialize_file(&self.path, &s)
+            .map_err(|e| syntax_error(self.name(), self.format.as_ref(), &s, e))?;
         Ok(Some(v))
     }
+
+    fn load_layers(&self) -> Result<Vec<Layer>, ReadError> {
+        if !self.required && !self.path.is_file() {
+            return Ok(Vec::new());
+        }
+
+        let s = std::fs::read_to_string(&self.path)?;
+        let docs = self
+            .format
+            .deserialize_file_documents(&self.path, &s)
+            .map_err(|e| syntax_error(self.name(), self.format.as_ref(), &s, e))?;
+        Ok(documents_to_layers(self.name(), &self.format, &s, docs))
+    }
+
+    fn watch_paths(&self) -> Vec<PathBuf> {
+        let mut paths = vec![self.path.clone()];
+        // Unreadable files will fail to load anyway
+        if let Ok(s) = std::fs::read_to_string(&self.path) {
+            paths.extend(self.format.file_dependencies(&self.path, &s));
+        }
+        paths
+    }
 }
 
 /////////////////////////////////////////////////////////////////////////////////////////
@@ -144,17 +345,24 @@ where
 /// `Env` looks for variables starting with `prefix` and splits the remainder
 /// of the variable name by `separator` to produce nested keys in the
 /// resulting JSON object.
-pub struct Env<Fmt> {
+///
+/// Values of variables are parsed using the specified format, that can also be
+/// chosen at runtime via [`Env::with_format()`]. Formats without types (see
+/// [`Conversion::Types`]) take values as strings converted to the types of config
+/// fields.
+pub struct Env<Fmt: ?Sized = dyn DynFormat> {
     prefix: String,
     separator: Cow<'static, str>,
-    _p: PhantomData<Fmt>,
+    format: Arc<dyn DynFormat>,
+    _p: PhantomData<fn() -> Box<Fmt>>,
 }
 
-impl<Fmt> Clone for Env<Fmt> {
+impl<Fmt: ?Sized> Clone for Env<Fmt> {
     fn clone(&self) -> Self {
         Self {
             prefix: self.prefix.clone(),
             separator: self.separator.clone(),
+            format: self.format.clone(),
             _p: PhantomData,
         }
     }
@@ -162,21 +370,34 @@ impl<Fmt> Clone for Env<Fmt> {
 
 impl<Fmt> Env<Fmt>
 where
-    Fmt: Format,
+    Fmt: Format + 'static,
 {
     pub fn new(prefix: impl Into<String>, separator: impl Into<Cow<'static, str>>) -> Self {
         Self {
             prefix: prefix.into(),
             separator: separator.into(),
+            format: Arc::new(FormatAdapter::<Fmt>::new()),
             _p: PhantomData,
         }
     }
 }
 
-impl<Fmt> Source for Env<Fmt>
-where
-    Fmt: Format,
-{
+impl Env {
+    pub fn with_format(
+        prefix: impl Into<String>,
+        separator: impl Into<Cow<'static, str>>,
+        format: impl Into<Arc<dyn DynFormat>>,
+    ) -> Self {
+        Self {
+            prefix: prefix.into(),
+            separator: separator.into(),
+            format: format.into(),
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<Fmt: ?Sized> Source for Env<Fmt> {
     fn name(&self) -> std::borrow::Cow<'static, str> {
         format!("Env {}*{}**", self.prefix, self.separator).into()
     }
@@ -189,23 +410,113 @@ where
                 continue;
             };
 
-            let value = Fmt::deserialize(&value).map_err(|e| ReadError::Serde(e.into()))?;
+            let value = parse_value(self.format.as_ref(), &value)?;
+
+            // Separator splits the name into literal keys, as names can't have quotes
+            let path: KeyPath = suffix
+                .split(self.separator.as_ref())
+                .map(|key| Segment::Key(key.to_string()))
+                .collect();
 
-            let mut current = &mut ret;
-            let mut segments = suffix.split(self.separator.as_ref()).peekable();
+            path.set(&mut ret, value)?;
+        }
 
-            while let Some(seg) = segments.next() {
-                if segments.peek().is_some() {
-                    current = &mut current[seg];
-                } else {
-                    current[seg] = value;
-                    break;
+        Ok(Some(ret))
+    }
+
+    fn load_layers(&self) -> Result<Vec<Layer>, ReadError> {
+        Ok(value_layer(self.name(), self.format.as_ref(), self.load()?))
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// [`Source`] of `path=value` overrides, e.g. passed as `--set database.port=5432`
+/// command line arguments.
+///
+/// Paths use [`KeyPath`] syntax, so keys containing dots can be quoted like
+/// `labels."app.kubernetes.io/name"=web`. Indices and wildcards are rejected, as a
+/// source can't address entries set by other sources (use
+/// [`crate::Config::set_value()`] instead).
+///
+/// Values are parsed using the specified format, e.g. with `Overrides::<Yaml>` value
+/// of `port=5432` is a number, while formats without types take values as strings
+/// converted to the types of config fields. Later overrides of the same path win.
+pub struct Overrides<Fmt: ?Sized = dyn DynFormat> {
+    overrides: Vec<String>,
+    format: Arc<dyn DynFormat>,
+    _p: PhantomData<fn() -> Box<Fmt>>,
+}
+
+impl<Fmt: ?Sized> Clone for Overrides<Fmt> {
+    fn clone(&self) -> Self {
+        Self {
+            overrides: self.overrides.clone(),
+            format: self.format.clone(),
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<Fmt> Overrides<Fmt>
+where
+    Fmt: Format + 'static,
+{
+    pub fn new(overrides: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            overrides: overrides.into_iter().map(Into::into).collect(),
+            format: Arc::new(FormatAdapter::<Fmt>::new()),
+            _p: PhantomData,
+        }
+    }
+}
+
+impl Overrides {
+    pub fn with_format(
+        overrides: impl IntoIterator<Item = impl Into<String>>,
+        format: impl Into<Arc<dyn DynFormat>>,
+    ) -> Self {
+        Self {
+            overrides: overrides.into_iter().map(Into::into).collect(),
+            format: format.into(),
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<Fmt: ?Sized> Source for Overrides<Fmt> {
+    fn name(&self) -> std::borrow::Cow<'static, str> {
+        "<overrides>".into()
+    }
+
+    fn load(&self) -> Result<Option<Value>, ReadError> {
+        if self.overrides.is_empty() {
+            return Ok(None);
+        }
+
+        let mut ret = Value::Object(Default::default());
+
+        for assignment in &self.overrides {
+            let (path, value) = KeyPath::parse_assignment(assignment)?;
+
+            if !path.is_keys_only() {
+                return Err(PathError::Unsupported {
+                    path: path.to_string(),
+                    reason: "overrides can only set keys",
                 }
+                .into());
             }
+
+            let value = parse_value(self.format.as_ref(), value)?;
+            path.set(&mut ret, value)?;
         }
 
         Ok(Some(ret))
     }
+
+    fn load_layers(&self) -> Result<Vec<Layer>, ReadError> {
+        Ok(value_layer(self.name(), self.format.as_ref(), self.load()?))
+    }
 }
 
 /////////////////////////////////////////////////////////////////////////////////////////
@@ -45,7 +45,9 @@ impl std::convert::TryFrom<&str> for UrlOrPath {
         match url::Url::parse(s) {
             Ok(url) => Ok(Self(url)),
             Err(err) => match PathBuf::from(s).canonicalize() {
-                Ok(path) => Ok(Self(url::Url::from_directory_path(path).unwrap())),
+                Ok(path) => url::Url::from_directory_path(path)
+                    .map(Self)
+                    .map_err(|_| err),
                 Err(_) => Err(err),
             },
         }
@@ -0,0 +1,342 @@
+#![cfg(feature = "watch")]
+
+use std::collections::VecDeque;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock, Weak};
+use std::time::Duration;
+
+use notify::{EventKind, RecursiveMode, Watcher as _};
+
+use crate::errors::ReadError;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Default quiet period after the last change before config is reloaded, see
+/// [`crate::Config::with_watch_debounce()`]
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(200);
+
+type LoadFn<Cfg> = dyn Fn() -> Loaded<Cfg> + Send + Sync;
+type SubscriberClb<Cfg> = dyn Fn(&Arc<Cfg>, &Arc<Cfg>) + Send + Sync;
+type ErrorClb = dyn Fn(&ReadError) + Send + Sync;
+
+/// Result of loading the config together with the files it was read from
+pub(crate) type Loaded<Cfg> = (Result<Cfg, ReadError>, Vec<PathBuf>);
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Handle to a config that is reloaded in background when files of its sources
+/// change, created by [`crate::Config::watch()`].
+///
+/// When the changed config fails to parse or validate the last good one is kept
+/// and the error is passed to [`Live::on_error()`] callbacks.
+///
+/// Cloning the handle is cheap and all clones observe the same config. Watching
+/// stops when the last clone is dropped.
+pub struct Live<Cfg> {
+    inner: Arc<Inner<Cfg>>,
+}
+
+struct Inner<Cfg> {
+    current: RwLock<Arc<Cfg>>,
+    load: Box<LoadFn<Cfg>>,
+    subscribers: Mutex<Vec<Arc<SubscriberClb<Cfg>>>>,
+    error_clbs: Mutex<Vec<Arc<ErrorClb>>>,
+    // Serializes reloads so changes are queued in order
+    reload_lock: Mutex<()>,
+    changes: Mutex<Changes<Cfg>>,
+    paths: Mutex<Vec<PathBuf>>,
+    targets: Mutex<Vec<Target>>,
+    watcher: Mutex<Option<notify::RecommendedWatcher>>,
+}
+
+/// Changes waiting to be passed to subscribers. Subscribers are called without
+/// holding any locks, so they can reload the config or subscribe again, in which
+/// case the new changes are delivered by the thread that is already notifying.
+struct Changes<Cfg> {
+    queue: VecDeque<(Arc<Cfg>, Arc<Cfg>)>,
+    notifying: bool,
+}
+
+impl<Cfg> Clone for Live<Cfg> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<Cfg> Live<Cfg>
+where
+    Cfg: Send + Sync + 'static,
+{
+    /// Takes the initially loaded config and starts watching its files
+    pub(crate) fn spawn(
+        initial: Loaded<Cfg>,
+        load: impl Fn() -> Loaded<Cfg> + Send + Sync + 'static,
+        debounce: Duration,
+    ) -> Result<Self, ReadError> {
+        let (initial, paths) = initial;
+
+        let inner = Arc::new(Inner {
+            current: RwLock::new(Arc::new(initial?)),
+            load: Box::new(load),
+            subscribers: Mutex::new(Vec::new()),
+            error_clbs: Mutex::new(Vec::new()),
+            reload_lock: Mutex::new(()),
+            changes: Mutex::new(Changes {
+                queue: VecDeque::new(),
+                notifying: false,
+            }),
+            paths: Mutex::new(Vec::new()),
+            targets: Mutex::new(Vec::new()),
+            watcher: Mutex::new(None),
+        });
+
+        let watcher = start_watcher(Arc::downgrade(&inner), debounce)?;
+        *lock(&inner.watcher) = Some(watcher);
+        inner.set_paths(paths)?;
+
+        Ok(Self { inner })
+    }
+
+    /// Returns the current config
+    pub fn load(&self) -> Arc<Cfg> {
+        self.inner
+            .current
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Files being watched, updated on every reload, e.g. when an `!include` is added
+    pub fn paths(&self) -> Vec<PathBuf> {
+        lock(&self.inner.paths).clone()
+    }
+
+    /// Registers a callback that receives the old and the new config after every
+    /// successful reload
+    pub fn subscribe(&self, clb: impl Fn(&Arc<Cfg>, &Arc<Cfg>) + Send + Sync + 'static) {
+        lock(&self.inner.subscribers).push(Arc::new(clb));
+    }
+
+    /// Registers a callback that receives errors of background reloads, while the
+    /// last good config stays in use
+    pub fn on_error(&self, clb: impl Fn(&ReadError) + Send + Sync + 'static) {
+        lock(&self.inner.error_clbs).push(Arc::new(clb));
+    }
+
+    /// Reloads the config immediately, e.g. on `SIGHUP`.
+    ///
+    /// On error the current config is kept.
+    pub fn reload(&self) -> Result<Arc<Cfg>, ReadError> {
+        self.inner.reload()
+    }
+}
+
+impl<Cfg> Inner<Cfg> {
+    fn reload(&self) -> Result<Arc<Cfg>, ReadError> {
+        let new = {
+            let _guard = lock(&self.reload_lock);
+
+            let (res, paths) = (self.load)();
+            let watched = self.set_paths(paths);
+            let new = Arc::new(res?);
+            watched?;
+
+            let old = std::mem::replace(
+                &mut *self.current.write().unwrap_or_else(PoisonError::into_inner),
+                new.clone(),
+            );
+            lock(&self.changes).queue.push_back((old, new.clone()));
+            new
+        };
+
+        self.notify();
+        Ok(new)
+    }
+
+    /// Passes queued changes to subscribers unless another call is already doing so
+    fn notify(&self) {
+        {
+            let mut changes = lock(&self.changes);
+            if changes.notifying {
+                return;
+            }
+            changes.notifying = true;
+        }
+
+        // Lets later changes be delivered if a subscriber panics
+        let _guard = Notifying(&self.changes);
+
+        loop {
+            let Some((old, new)) = ({
+                let mut changes = lock(&self.changes);
+                let next = changes.queue.pop_front();
+                changes.notifying = next.is_some();
+                next
+            }) else {
+                return;
+            };
+
+            let subscribers = lock(&self.subscribers).clone();
+            for clb in subscribers {
+                clb(&old, &new);
+            }
+        }
+    }
+
+    fn report(&self, err: &ReadError) {
+        let error_clbs = lock(&self.error_clbs).clone();
+        for clb in error_clbs {
+            clb(err);
+        }
+    }
+
+    /// Replaces the watched files, starting to watch new directories and stopping
+    /// to watch ones that are no longer needed
+    fn set_paths(&self, paths: Vec<PathBuf>) -> Result<(), ReadError> {
+        // Directories that don't exist can't be watched
+        let new: Vec<_> = paths.iter().filter_map(|p| Target::new(p)).collect();
+
+        let mut targets = lock(&self.targets);
+        if let Some(watcher) = lock(&self.watcher).as_mut() {
+            let old_dirs = dirs(&targets);
+            let new_dirs = dirs(&new);
+            for dir in old_dirs.iter().filter(|d| !new_dirs.contains(d)) {
+                // Fails when the directory was removed, which also stops watching it
+                let _ = watcher.unwatch(dir);
+            }
+            for dir in new_dirs.iter().filter(|d| !old_dirs.contains(d)) {
+                watcher.watch(dir, RecursiveMode::NonRecursive)?;
+            }
+        }
+
+        *targets = new;
+        *lock(&self.paths) = paths;
+        Ok(())
+    }
+
+    fn is_relevant(&self, event: &notify::Result<notify::Event>) -> bool {
+        match event {
+            // Reading the files during reload produces access events
+            Ok(event) => {
+                let targets = lock(&self.targets);
+                !matches!(event.kind, EventKind::Access(_))
+                    && event
+                        .paths
+                        .iter()
+                        .any(|p| targets.iter().any(|t| t.matches(p)))
+            }
+            Err(_) => true,
+        }
+    }
+}
+
+/// Resets [`Changes::notifying`] when a subscriber panics
+struct Notifying<'a, Cfg>(&'a Mutex<Changes<Cfg>>);
+
+impl<Cfg> Drop for Notifying<'_, Cfg> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            lock(self.0).notifying = false;
+        }
+    }
+}
+
+fn lock<T>(m: &Mutex<T>) -> MutexGuard<'_, T> {
+    m.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// File to react to. Parent directory is watched instead of the file itself to
+/// notice files that are created later or replaced by editors via rename.
+struct Target {
+    dir: PathBuf,
+    name: OsString,
+}
+
+impl Target {
+    fn new(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_os_string();
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let dir = dir.canonicalize().ok()?;
+        Some(Self { dir, name })
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        if path.file_name() != Some(self.name.as_os_str()) {
+            return false;
+        }
+        let Some(dir) = path.parent() else {
+            return false;
+        };
+        dir == self.dir || dir.canonicalize().is_ok_and(|d| d == self.dir)
+    }
+}
+
+fn dirs(targets: &[Target]) -> Vec<&Path> {
+    let mut dirs: Vec<&Path> = targets.iter().map(|t| t.dir.as_path()).collect();
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+fn start_watcher<Cfg>(
+    inner: Weak<Inner<Cfg>>,
+    debounce: Duration,
+) -> Result<notify::RecommendedWatcher, ReadError>
+where
+    Cfg: Send + Sync + 'static,
+{
+    let (tx, rx) = mpsc::channel();
+    let watcher = notify::recommended_watcher(tx)?;
+
+    std::thread::Builder::new()
+        .name("setty-watch".into())
+        .spawn(move || watch_loop(rx, inner, debounce))?;
+
+    Ok(watcher)
+}
+
+/// Runs until the watcher is dropped together with the last [`Live`] handle
+fn watch_loop<Cfg>(
+    rx: mpsc::Receiver<notify::Result<notify::Event>>,
+    inner: Weak<Inner<Cfg>>,
+    debounce: Duration,
+) {
+    while let Ok(event) = rx.recv() {
+        let Some(inner) = inner.upgrade() else {
+            return;
+        };
+        if !inner.is_relevant(&event) {
+            continue;
+        }
+
+        let mut error = event.err();
+
+        // Wait for the writes to settle
+        loop {
+            match rx.recv_timeout(debounce) {
+                Ok(event) if inner.is_relevant(&event) => error = error.or(event.err()),
+                Ok(_) => {}
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+
+        if let Some(err) = error {
+            inner.report(&err.into());
+        }
+        if let Err(err) = inner.reload() {
+            inner.report(&err);
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
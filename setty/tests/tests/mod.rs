@@ -2,9 +2,16 @@ mod test_combine;
 mod test_config;
 mod test_derive;
 mod test_deserialize;
+mod test_diagnostics;
+mod test_diff;
+mod test_errors;
 mod test_format;
+mod test_hostile;
 mod test_jsonschema;
 mod test_markdown;
+mod test_path;
+mod test_reload;
 mod test_serialize;
 mod test_source;
 mod test_types;
+mod test_watch;
@@ -124,6 +124,733 @@ fn test_config_ops() {
 
 /////////////////////////////////////////////////////////////////////////////////////////
 
+#[cfg(feature = "fmt-yaml")]
+#[test]
+fn test_config_get_typed() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.yaml");
+
+    std::fs::write(
+        &path,
+        indoc::indoc!(
+            r#"
+            database:
+                kind: Postgres
+                schema_name: foo
+            encryption:
+                key: 5
+            "#
+        ),
+    )
+    .unwrap();
+
+    let fig = setty::Config::<MyConfig>::new()
+        .with_source(setty::source::File::<setty::format::Yaml>::new(&path));
+
+    pretty_assertions::assert_eq!(
+        fig.get::<String>("database.schema_name", false).unwrap(),
+        Some("foo".to_string())
+    );
+    pretty_assertions::assert_eq!(fig.get::<String>("database.host", false).unwrap(), None);
+    pretty_assertions::assert_eq!(
+        fig.get::<String>("database.host", true).unwrap(),
+        Some("localhost".to_string())
+    );
+    pretty_assertions::assert_eq!(
+        fig.get::<DatabaseConfig>("database", true).unwrap(),
+        Some(DatabaseConfig::Postgres(PostgresDatabaseConfig {
+            schema_name: "foo".into(),
+            host: "localhost".into(),
+        }))
+    );
+
+    // Errors name the full path and the location of the value
+    let err = fig.get::<bool>("database.schema_name", false).unwrap_err();
+    assert_eq!(err.path(), Some("database.schema_name"));
+    assert!(err.location().is_some(), "{err}");
+
+    let err = fig
+        .get::<EncryptionConfig>("encryption", false)
+        .unwrap_err();
+    assert_eq!(err.path(), Some("encryption.key"), "{err}");
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_config_extract_at() {
+    #[derive(setty::Config)]
+    struct Cfg {
+        #[config(default)]
+        server: Server,
+        database: Option<Database>,
+    }
+
+    #[derive(setty::Config, setty::Default)]
+    struct Server {
+        #[config(default = 8080)]
+        port: u16,
+    }
+
+    #[derive(setty::Config)]
+    struct Database {
+        url: String,
+        #[deprecated = "Use url instead"]
+        host: Option<String>,
+        #[config(default = 10)]
+        pool_size: u32,
+    }
+
+    let deprecated = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let fig = |value: serde_json::Value| {
+        let dep = deprecated.clone();
+        setty::Config::<Cfg>::new()
+            .with_source(value)
+            .with_deprecation_clb(move |path: &[&str], _: Option<&str>, _: Option<&str>| {
+                dep.lock().unwrap().push(path.join("."))
+            })
+    };
+
+    // Section with defaults only
+    let server: Server = fig(serde_json::json!({})).extract_at("server").unwrap();
+    assert_eq!(server.port, 8080);
+
+    // Invalid sibling sections don't matter
+    let db: Database = fig(serde_json::json!({
+        "server": {"port": "eighty"},
+        "database": {"url": "pg://", "host": "db"},
+    }))
+    .extract_at("database")
+    .unwrap();
+    assert_eq!(db.url, "pg://");
+    assert_eq!(db.pool_size, 10);
+    assert_eq!(*deprecated.lock().unwrap(), ["database.host"]);
+
+    // Errors name the full path
+    let err = fig(serde_json::json!({"database": {"url": 1}}))
+        .extract_at::<Database>("database")
+        .err()
+        .unwrap();
+    assert_eq!(err.path(), Some("database.url"), "{err}");
+
+    let err = fig(serde_json::json!({}))
+        .extract_at::<Database>("database")
+        .err()
+        .unwrap();
+    assert_eq!(err.path(), Some("database"), "{err}");
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "derive-validate")]
+#[test]
+fn test_config_extract_at_validates() {
+    #[derive(setty::Config)]
+    struct Cfg {
+        database: Database,
+    }
+
+    #[derive(setty::Config)]
+    struct Database {
+        #[config(validate(range(min = 1)))]
+        pool_size: u32,
+    }
+
+    let fig =
+        setty::Config::<Cfg>::new().with_source(serde_json::json!({"database": {"pool_size": 0}}));
+
+    assert!(fig.extract_at::<Database>("database").is_err());
+
+    let err = fig
+        .with_all_errors(true)
+        .extract_at::<Database>("database")
+        .err()
+        .unwrap();
+    pretty_assertions::assert_eq!(
+        err.to_string(),
+        indoc::indoc!(
+            "
+            Found 1 error in config:
+            - Validation failed at `database.pool_size`: range (min = 1) in <raw value>"
+        )
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "fmt-toml")]
+#[test]
+fn test_config_edit_toml_preserves_formatting() {
+    use setty::format::Toml;
+
+    #[derive(setty::Config)]
+    struct Cfg {
+        #[config(default)]
+        server: Server,
+        #[config(default)]
+        database: Database,
+    }
+
+    #[derive(setty::Config, setty::Default)]
+    struct Server {
+        #[config(default)]
+        host: String,
+        #[config(default)]
+        port: u16,
+    }
+
+    #[derive(setty::Config, setty::Default)]
+    struct Database {
+        #[config(default)]
+        url: String,
+        #[config(default)]
+        pool_size: u32,
+    }
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.toml");
+
+    std::fs::write(
+        &path,
+        indoc::indoc!(
+            r#"
+            # Main config
+
+            [server]
+            host   = "localhost" # Change me
+            # Port to listen on
+            port = 8080
+            "#
+        ),
+    )
+    .unwrap();
+
+    let fig = setty::Config::<Cfg>::new().with_source(setty::source::File::<Toml>::new(&path));
+
+    fig.set_value::<Toml>("server.host", "example.com", &path)
+        .unwrap();
+    fig.set_value::<Toml>("database.url", "pg://db", &path)
+        .unwrap();
+    fig.set_value::<Toml>("database.pool_size", 10, &path)
+        .unwrap();
+
+    pretty_assertions::assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        indoc::indoc!(
+            r#"
+            # Main config
+
+            [server]
+            host   = "example.com" # Change me
+            # Port to listen on
+            port = 8080
+
+            [database]
+            url = "pg://db"
+            pool_size = 10
+            "#
+        ),
+    );
+
+    fig.unset_value::<Toml>("server.host", &path).unwrap();
+    fig.unset_value::<Toml>("database", &path).unwrap();
+
+    pretty_assertions::assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        indoc::indoc!(
+            r#"
+            # Main config
+
+            [server]
+            # Port to listen on
+            port = 8080
+            "#
+        ),
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_config_edit_yaml_preserves_formatting() {
+    use setty::format::Yaml;
+
+    #[derive(setty::Config)]
+    struct Cfg {
+        #[config(default)]
+        server: Server,
+        #[config(default)]
+        database: Database,
+    }
+
+    #[derive(setty::Config, setty::Default)]
+    struct Server {
+        #[config(default)]
+        host: String,
+        #[config(default)]
+        port: u16,
+    }
+
+    #[derive(setty::Config, setty::Default)]
+    struct Database {
+        #[config(default)]
+        host: String,
+        #[config(default)]
+        url: String,
+        #[config(default)]
+        pool_size: u32,
+    }
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.yaml");
+
+    std::fs::write(
+        &path,
+        indoc::indoc!(
+            r#"
+            # Main config
+            server:
+              host: &host localhost # Change me
+              # Port to listen on
+              port: 8080
+
+            database:
+              host: *host
+            "#
+        ),
+    )
+    .unwrap();
+
+    let fig = setty::Config::<Cfg>::new().with_source(setty::source::File::<Yaml>::new(&path));
+
+    fig.set_value::<Yaml>("server.port", 9090, &path).unwrap();
+    fig.set_value::<Yaml>("database.url", "pg://db", &path)
+        .unwrap();
+    fig.set_value::<Yaml>("database.pool_size", 10, &path)
+        .unwrap();
+
+    pretty_assertions::assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        indoc::indoc!(
+            r#"
+            # Main config
+            server:
+              host: &host localhost # Change me
+              # Port to listen on
+              port: 9090
+
+            database:
+              host: *host
+              url: pg://db
+              pool_size: 10
+            "#
+        ),
+    );
+
+    fig.unset_value::<Yaml>("server.port", &path).unwrap();
+    fig.unset_value::<Yaml>("database.host", &path).unwrap();
+
+    pretty_assertions::assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        indoc::indoc!(
+            r#"
+            # Main config
+            server:
+              host: &host localhost # Change me
+              # Port to listen on
+
+            database:
+              url: pg://db
+              pool_size: 10
+            "#
+        ),
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "fmt-yaml")]
+#[test]
+fn test_config_edit_yaml_new_keys_and_sequences() {
+    use setty::format::Yaml;
+
+    #[derive(setty::Config)]
+    struct Cfg {
+        #[config(default)]
+        port: u16,
+        #[config(default)]
+        hosts: Vec<String>,
+        #[config(default)]
+        database: Database,
+    }
+
+    #[derive(setty::Config, setty::Default)]
+    struct Database {
+        #[config(default)]
+        url: String,
+    }
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.yaml");
+
+    std::fs::write(&path, "# top\nport: 81 # the port\n").unwrap();
+
+    let fig = setty::Config::<Cfg>::new().with_source(setty::source::File::<Yaml>::new(&path));
+
+    fig.set_value::<Yaml>("database.url", "pg://db", &path)
+        .unwrap();
+    fig.append_value::<Yaml>("hosts", "a", &path).unwrap();
+
+    pretty_assertions::assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        indoc::indoc!(
+            r#"
+            # top
+            port: 81 # the port
+            database:
+              url: pg://db
+            hosts:
+              - a
+            "#
+        ),
+    );
+
+    std::fs::write(
+        &path,
+        indoc::indoc!(
+            r#"
+            hosts:
+              - a # first
+              - b # second
+              - c
+            port: 81
+            "#
+        ),
+    )
+    .unwrap();
+
+    fig.append_value::<Yaml>("hosts", "d", &path).unwrap();
+    fig.set_value::<Yaml>("hosts[1]", "x", &path).unwrap();
+    fig.remove_value::<Yaml>("hosts", 2, &path).unwrap();
+
+    pretty_assertions::assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        indoc::indoc!(
+            r#"
+            hosts:
+              - a # first
+              - x # second
+              - d
+            port: 81
+            "#
+        ),
+    );
+
+    // Documents that can't be patched are left as is
+    std::fs::write(&path, "{port: 81} # flow\n").unwrap();
+    let err = fig.set_value::<Yaml>("port", 82, &path).unwrap_err();
+    assert!(
+        err.to_string().contains("without losing its comments"),
+        "{err}"
+    );
+    pretty_assertions::assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "{port: 81} # flow\n"
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "preserve-order")]
+#[cfg(feature = "fmt-json")]
+#[test]
+fn test_config_preserves_key_order() {
+    use setty::format::{Format, JsonPretty};
+
+    #[derive(setty::Config)]
+    struct Cfg {
+        #[config(default)]
+        server: Server,
+    }
+
+    #[derive(setty::Config, setty::Default)]
+    struct Server {
+        #[config(default)]
+        port: u16,
+        #[config(default)]
+        host: String,
+        #[config(default)]
+        timeout: u32,
+    }
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.json");
+
+    std::fs::write(&path, r#"{"server": {"timeout": 5, "host": "localhost"}}"#).unwrap();
+
+    let fig =
+        setty::Config::<Cfg>::new().with_source(setty::source::File::<JsonPretty>::new(&path));
+
+    // File order first, then defaults in the field order
+    pretty_assertions::assert_eq!(
+        JsonPretty::serialize(&fig.data(true).unwrap()).unwrap(),
+        indoc::indoc!(
+            r#"
+            {
+              "server": {
+                "timeout": 5,
+                "host": "localhost",
+                "port": 0
+              }
+            }"#
+        ),
+    );
+
+    fig.set_value::<JsonPretty>("server.port", 8080, &path)
+        .unwrap();
+    fig.unset_value::<JsonPretty>("server.timeout", &path)
+        .unwrap();
+
+    pretty_assertions::assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        indoc::indoc!(
+            r#"
+            {
+              "server": {
+                "host": "localhost",
+                "port": 8080
+              }
+            }"#
+        ),
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "fmt-yaml")]
+#[test]
+fn test_config_array_ops() {
+    #[derive(setty::Config)]
+    struct Cfg {
+        #[config(default)]
+        hosts: Vec<String>,
+        #[config(default)]
+        ports: Vec<u16>,
+        #[config(default)]
+        clusters: std::collections::BTreeMap<String, Cluster>,
+    }
+
+    #[derive(setty::Config)]
+    struct Cluster {
+        #[config(default)]
+        hosts: Vec<String>,
+    }
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.yaml");
+
+    let fig = setty::Config::<Cfg>::new()
+        .with_source(setty::source::File::<setty::format::Yaml>::new(&path).required(false));
+
+    // Creates the file and the array
+    fig.append_value::<setty::format::Yaml>("hosts", "b", &path)
+        .unwrap();
+    fig.append_value::<setty::format::Yaml>("hosts", "d", &path)
+        .unwrap();
+    fig.insert_value::<setty::format::Yaml>("hosts", 0, "a", &path)
+        .unwrap();
+    fig.insert_value::<setty::format::Yaml>("hosts", 2, "c", &path)
+        .unwrap();
+    fig.insert_value::<setty::format::Yaml>("hosts", 4, "e", &path)
+        .unwrap();
+
+    pretty_assertions::assert_eq!(fig.extract().unwrap().hosts, ["a", "b", "c", "d", "e"]);
+
+    pretty_assertions::assert_eq!(
+        fig.remove_value::<setty::format::Yaml>("hosts", 1, &path)
+            .unwrap(),
+        [serde_json::json!("b")]
+    );
+    pretty_assertions::assert_eq!(fig.extract().unwrap().hosts, ["a", "c", "d", "e"]);
+
+    // Out of bounds
+    pretty_assertions::assert_eq!(
+        fig.insert_value::<setty::format::Yaml>("hosts", 5, "x", &path)
+            .unwrap_err()
+            .to_string(),
+        "Index 5 is out of bounds at `hosts` of length 4"
+    );
+    pretty_assertions::assert_eq!(
+        fig.remove_value::<setty::format::Yaml>("ports", 0, &path)
+            .unwrap_err()
+            .to_string(),
+        "Index 0 is out of bounds at `ports` of length 0"
+    );
+
+    // Not an array
+    std::fs::write(&path, "hosts: a\n").unwrap();
+    pretty_assertions::assert_eq!(
+        fig.append_value::<setty::format::Yaml>("hosts", "b", &path)
+            .unwrap_err()
+            .to_string(),
+        "Expected array at `hosts`, found string"
+    );
+
+    std::fs::write(
+        &path,
+        indoc::indoc!(
+            r#"
+            hosts: [a]
+            clusters:
+              east:
+                hosts: []
+              west:
+                hosts: [x]
+            "#
+        ),
+    )
+    .unwrap();
+
+    // Invalid elements are rejected before writing
+    assert!(
+        fig.append_value::<setty::format::Yaml>("ports", "eighty", &path)
+            .is_err()
+    );
+
+    // Wildcards edit all matching arrays
+    fig.append_value::<setty::format::Yaml>("clusters.*.hosts", "y", &path)
+        .unwrap();
+    pretty_assertions::assert_eq!(
+        fig.remove_value::<setty::format::Yaml>("clusters.*.hosts", 0, &path)
+            .unwrap(),
+        [serde_json::json!("y"), serde_json::json!("x")]
+    );
+
+    // Only the changed sequence items are patched
+    pretty_assertions::assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        indoc::indoc!(
+            r#"
+            hosts: [a]
+            clusters:
+              east:
+                hosts: []
+              west:
+                hosts:
+                  - y
+            "#
+        )
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "fmt-yaml")]
+#[test]
+fn test_config_edit_checks_merged_config() {
+    #[derive(setty::Config)]
+    struct Cfg {
+        database: Database,
+    }
+
+    #[derive(setty::Config)]
+    struct Database {
+        url: String,
+        #[config(default = 4)]
+        pool_size: u32,
+        #[deprecated = "Use `url` instead"]
+        password: Option<String>,
+    }
+
+    let tmp = tempfile::tempdir().unwrap();
+    let base = tmp.path().join("base.yaml");
+    let local = tmp.path().join("local.yaml");
+
+    std::fs::write(&base, "database:\n  url: pg://db\n").unwrap();
+
+    let deprecated = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+    let fig = setty::Config::<Cfg>::new()
+        .with_source(setty::source::File::<setty::format::Yaml>::new(&base))
+        .with_source(setty::source::File::<setty::format::Yaml>::new(&local).required(false))
+        .with_deprecation_clb({
+            let deprecated = deprecated.clone();
+            move |path: &[&str], _: Option<&str>, _: Option<&str>| {
+                deprecated.lock().unwrap().push(path.join("."))
+            }
+        });
+
+    // Required fields can live in other files
+    fig.set_value::<setty::format::Yaml>("database.pool_size", 8, &local)
+        .unwrap();
+    pretty_assertions::assert_eq!(fig.extract().unwrap().database.pool_size, 8);
+
+    // Errors point at the edited file
+    let err = fig
+        .set_value::<setty::format::Yaml>("database.pool_size", "eight", &local)
+        .unwrap_err();
+    let setty::errors::WriteError::Read(setty::errors::ReadError::InvalidType {
+        path,
+        location: Some(location),
+        ..
+    }) = err
+    else {
+        panic!("{err:?}");
+    };
+    pretty_assertions::assert_eq!(path, "database.pool_size");
+    pretty_assertions::assert_eq!(location.source_name, local.display().to_string());
+    pretty_assertions::assert_eq!(
+        std::fs::read_to_string(&local).unwrap(),
+        "database:\n  pool_size: 8\n"
+    );
+
+    // Edits that break the merged config are rejected before writing
+    assert!(
+        fig.unset_value::<setty::format::Yaml>("database.url", &base)
+            .is_err()
+    );
+    pretty_assertions::assert_eq!(
+        std::fs::read_to_string(&base).unwrap(),
+        "database:\n  url: pg://db\n"
+    );
+
+    // Deprecated fields are reported
+    fig.set_value::<setty::format::Yaml>("database.password", "swordfish", &local)
+        .unwrap();
+    pretty_assertions::assert_eq!(*deprecated.lock().unwrap(), ["database.password"]);
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "fmt-yaml")]
+#[cfg(feature = "derive-validate")]
+#[test]
+fn test_config_edit_validates() {
+    #[derive(setty::Config)]
+    struct Cfg {
+        #[config(default = 4, validate(range(min = 1)))]
+        pool_size: u32,
+    }
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.yaml");
+
+    let fig = setty::Config::<Cfg>::new()
+        .with_source(setty::source::File::<setty::format::Yaml>::new(&path).required(false));
+
+    assert!(
+        fig.set_value::<setty::format::Yaml>("pool_size", 0, &path)
+            .is_err()
+    );
+    assert!(!path.exists());
+
+    fig.set_value::<setty::format::Yaml>("pool_size", 1, &path)
+        .unwrap();
+    pretty_assertions::assert_eq!(fig.extract().unwrap().pool_size, 1);
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
 #[test]
 fn test_config_reports_deprecated() {
     #[derive(setty::Config)]
@@ -194,3 +921,123 @@ fn test_config_reports_deprecated() {
 }
 
 /////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_config_env_vars() {
+    let vars = setty::Config::<MyConfig>::new().env_vars("APP__", "__");
+
+    let vars: Vec<_> = vars
+        .iter()
+        .map(|v| {
+            (
+                v.name.as_str(),
+                v.path.as_str(),
+                v.typ.as_str(),
+                v.values.clone(),
+                v.default.clone(),
+            )
+        })
+        .collect();
+
+    // Properties are listed in declaration order
+    #[cfg(feature = "preserve-order")]
+    pretty_assertions::assert_eq!(
+        vars,
+        [
+            (
+                "APP__database__database_path",
+                "database.database_path",
+                "string",
+                vec![],
+                Some(".kamu/db.sqlite".into()),
+            ),
+            (
+                "APP__database__kind",
+                "database.kind",
+                "string",
+                vec!["Sqlite".into(), "Postgres".into()],
+                Some("Sqlite".into()),
+            ),
+            (
+                "APP__database__schema_name",
+                "database.schema_name",
+                "string",
+                vec![],
+                None,
+            ),
+            (
+                "APP__database__host",
+                "database.host",
+                "string",
+                vec![],
+                Some("localhost".into()),
+            ),
+            (
+                "APP__encryption__key",
+                "encryption.key",
+                "string",
+                vec![],
+                None,
+            ),
+            (
+                "APP__encryption__algo",
+                "encryption.algo",
+                "string",
+                vec!["Aes".into(), "Rsa".into()],
+                Some("Aes".into()),
+            ),
+        ]
+    );
+
+    // Properties of each struct and enum variant are listed alphabetically
+    #[cfg(not(feature = "preserve-order"))]
+    pretty_assertions::assert_eq!(
+        vars,
+        [
+            (
+                "APP__database__database_path",
+                "database.database_path",
+                "string",
+                vec![],
+                Some(".kamu/db.sqlite".into()),
+            ),
+            (
+                "APP__database__kind",
+                "database.kind",
+                "string",
+                vec!["Sqlite".into(), "Postgres".into()],
+                Some("Sqlite".into()),
+            ),
+            (
+                "APP__database__host",
+                "database.host",
+                "string",
+                vec![],
+                Some("localhost".into()),
+            ),
+            (
+                "APP__database__schema_name",
+                "database.schema_name",
+                "string",
+                vec![],
+                None,
+            ),
+            (
+                "APP__encryption__algo",
+                "encryption.algo",
+                "string",
+                vec!["Aes".into(), "Rsa".into()],
+                Some("Aes".into()),
+            ),
+            (
+                "APP__encryption__key",
+                "encryption.key",
+                "string",
+                vec![],
+                None,
+            ),
+        ]
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
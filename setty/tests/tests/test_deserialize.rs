@@ -137,7 +137,7 @@ fn test_unrecognized_field_rejected() {
 
     pretty_assertions::assert_eq!(
         err.to_string(),
-        "unknown field `schema_namez`, expected `schema_name` or `host`"
+        "Unknown field `database.schema_namez` in <raw value>, did you mean `database.schema_name`?"
     );
 }
 
@@ -155,7 +155,10 @@ fn test_required_field() {
         .extract()
         .expect_err("Expected error");
 
-    pretty_assertions::assert_eq!(err.to_string(), "missing field `key`");
+    pretty_assertions::assert_eq!(
+        err.to_string(),
+        "Missing required field `encryption.key` in <raw value>"
+    );
 
     // Required field present
     let cfg: MyConfig = setty::Config::new()
@@ -0,0 +1,200 @@
+#![cfg(feature = "diagnostics")]
+#![cfg(feature = "derive-jsonschema")]
+#![cfg(feature = "fmt-yaml")]
+
+use setty::diagnostics::Report;
+use setty::format::Yaml;
+use setty::source::File;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(setty::Config)]
+struct Cfg {
+    #[config(default)]
+    server: Server,
+}
+
+#[derive(setty::Config, setty::Default)]
+struct Server {
+    /// Host to bind to
+    #[config(default)]
+    host: String,
+
+    /// Port to listen on
+    #[config(default)]
+    port: u16,
+
+    #[deprecated(since = "1.1.0", note = "Use `host` instead")]
+    addr: Option<String>,
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_diagnostics_report() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.yaml");
+
+    std::fs::write(
+        &path,
+        indoc::indoc!(
+            "
+            server:
+              hots: localhost
+              port: eighty
+              addr: 1
+            "
+        ),
+    )
+    .unwrap();
+
+    let config = setty::Config::<Cfg>::new()
+        .with_source(File::<Yaml>::new(&path))
+        .with_all_errors(true)
+        .with_deprecation_clb(|_, _, _| {});
+
+    let report = config
+        .report(config.extract().unwrap_err())
+        .with_color(false);
+
+    // Errors are reported in order of fields
+    #[cfg(feature = "preserve-order")]
+    pretty_assertions::assert_eq!(
+        report.to_string(),
+        indoc::formatdoc!(
+            r#"
+            error: unknown field `server.hots`
+             --> {path}:2:3
+              |
+            2 |   hots: localhost
+              |   ^^^^ unknown field
+              |
+              = help: did you mean `server.host`?
+              = note: Host to bind to
+
+            error: invalid type at `server.port`
+             --> {path}:3:3
+              |
+            3 |   port: eighty
+              |   ^^^^ expected u16, found string "eighty"
+              |
+              = note: Port to listen on
+
+            error: invalid type at `server.addr`
+             --> {path}:4:3
+              |
+            4 |   addr: 1
+              |   ^^^^ expected string or null, found integer `1`
+              |
+              = warning: `server.addr` is deprecated since version 1.1.0: Use `host` instead
+
+            error: found 3 errors in config"#,
+            path = path.display(),
+        )
+    );
+
+    // Errors are reported in alphabetical order of fields
+    #[cfg(not(feature = "preserve-order"))]
+    pretty_assertions::assert_eq!(
+        report.to_string(),
+        indoc::formatdoc!(
+            r#"
+            error: invalid type at `server.addr`
+             --> {path}:4:3
+              |
+            4 |   addr: 1
+              |   ^^^^ expected string or null, found integer `1`
+              |
+              = warning: `server.addr` is deprecated since version 1.1.0: Use `host` instead
+
+            error: unknown field `server.hots`
+             --> {path}:2:3
+              |
+            2 |   hots: localhost
+              |   ^^^^ unknown field
+              |
+              = help: did you mean `server.host`?
+              = note: Host to bind to
+
+            error: invalid type at `server.port`
+             --> {path}:3:3
+              |
+            3 |   port: eighty
+              |   ^^^^ expected u16, found string "eighty"
+              |
+              = note: Port to listen on
+
+            error: found 3 errors in config"#,
+            path = path.display(),
+        )
+    );
+
+    // Colors can be enabled
+    let report = config
+        .report(config.extract().unwrap_err())
+        .with_color(true);
+    assert!(report.to_string().contains("\u{1b}["));
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_diagnostics_without_location() {
+    #[derive(setty::Config)]
+    struct Cfg {
+        name: String,
+    }
+
+    // Can be used with `?`
+    fn extract() -> Result<Cfg, Report> {
+        Ok(setty::Config::<Cfg>::new()
+            .with_source(serde_json::json!({"name": 1}))
+            .extract()?)
+    }
+
+    let report = extract().err().unwrap().with_color(false);
+
+    pretty_assertions::assert_eq!(
+        format!("{report:?}"),
+        indoc::indoc!(
+            r#"
+            error: invalid type at `name`
+             --> <raw value>
+              = note: expected a string, found integer `1`"#
+        )
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_diagnostics_quoted_keys() {
+    #[derive(setty::Config)]
+    struct Cfg {
+        #[config(default)]
+        routes: std::collections::BTreeMap<String, Route>,
+    }
+
+    #[derive(setty::Config)]
+    struct Route {
+        /// Port of the upstream
+        port: u16,
+    }
+
+    let config = setty::Config::<Cfg>::new().with_source(setty::source::RawData::<Yaml>::new(
+        "routes:\n  api.v1:\n    port: eighty\n",
+    ));
+
+    let report = config
+        .report(config.extract().unwrap_err())
+        .with_color(false)
+        .to_string();
+
+    assert!(
+        report.starts_with(r#"error: invalid type at `routes."api.v1".port`"#),
+        "{report}"
+    );
+    assert!(report.contains("= note: Port of the upstream"), "{report}");
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
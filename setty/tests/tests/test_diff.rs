@@ -0,0 +1,197 @@
+use setty::diff::ChangeKind;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_diff_values() {
+    let old = serde_json::json!({
+        "a/b": {"~c": 1},
+        "hosts": ["a", "b", "c"],
+        "labels": {"app.k8s.io/name": "web"},
+        "server": {"host": "localhost", "port": 8080},
+    });
+    let new = serde_json::json!({
+        "a/b": {"~c": 2},
+        "hosts": ["a", "x"],
+        "labels": {"app.k8s.io/name": "api"},
+        "server": {"port": 80, "tls": true},
+    });
+
+    let diff = setty::diff(&old, &new);
+
+    let summary: Vec<_> = diff
+        .changes
+        .iter()
+        .map(|c| (c.path.as_str(), c.pointer.as_str()))
+        .collect();
+    pretty_assertions::assert_eq!(
+        summary,
+        [
+            ("a/b.~c", "/a~1b/~0c"),
+            ("hosts[1]", "/hosts/1"),
+            ("hosts[2]", "/hosts/2"),
+            (r#"labels."app.k8s.io/name""#, "/labels/app.k8s.io~1name"),
+            ("server.host", "/server/host"),
+            ("server.port", "/server/port"),
+            ("server.tls", "/server/tls"),
+        ]
+    );
+
+    pretty_assertions::assert_eq!(
+        diff.to_string(),
+        indoc::indoc!(
+            r#"
+            ~ a/b.~c: 1 -> 2
+            ~ hosts[1]: "b" -> "x"
+            - hosts[2]: "c"
+            ~ labels."app.k8s.io/name": "web" -> "api"
+            - server.host: "localhost"
+            ~ server.port: 8080 -> 80
+            + server.tls: true"#
+        )
+    );
+
+    pretty_assertions::assert_eq!(
+        diff.to_json_patch(),
+        serde_json::json!([
+            {"op": "replace", "path": "/a~1b/~0c", "value": 2},
+            {"op": "replace", "path": "/hosts/1", "value": "x"},
+            {"op": "remove", "path": "/hosts/2"},
+            {"op": "replace", "path": "/labels/app.k8s.io~1name", "value": "api"},
+            {"op": "remove", "path": "/server/host"},
+            {"op": "replace", "path": "/server/port", "value": 80},
+            {"op": "add", "path": "/server/tls", "value": true},
+        ])
+    );
+
+    assert!(setty::diff(&old, &old.clone()).is_empty());
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_diff_arrays() {
+    let diff = setty::diff(
+        &serde_json::json!({"a": [1, 2, 3, 4]}),
+        &serde_json::json!({"a": [1]}),
+    );
+
+    // Removed from the end so indices in JSON Patch stay valid
+    pretty_assertions::assert_eq!(
+        diff.to_json_patch(),
+        serde_json::json!([
+            {"op": "remove", "path": "/a/3"},
+            {"op": "remove", "path": "/a/2"},
+            {"op": "remove", "path": "/a/1"},
+        ])
+    );
+
+    let diff = setty::diff(
+        &serde_json::json!({"a": [{"x": 1}]}),
+        &serde_json::json!({"a": [{"x": 2}, {"x": 3}]}),
+    );
+
+    pretty_assertions::assert_eq!(
+        diff.to_string(),
+        indoc::indoc!(
+            r#"
+            ~ a[0].x: 1 -> 2
+            + a[1]: {"x":3}"#
+        )
+    );
+
+    // Root of a different type
+    let diff = setty::diff(&serde_json::json!([]), &serde_json::json!(1));
+    pretty_assertions::assert_eq!(diff.to_string(), "~ <root>: [] -> 1");
+    pretty_assertions::assert_eq!(
+        diff.to_json_patch(),
+        serde_json::json!([{"op": "replace", "path": "", "value": 1}])
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "derive-jsonschema")]
+#[cfg(feature = "derive-serialize")]
+#[test]
+fn test_diff_configs() {
+    #[derive(setty::Config)]
+    struct Cfg {
+        #[config(default)]
+        name: String,
+        storage: Option<Storage>,
+    }
+
+    #[derive(setty::Config)]
+    enum Storage {
+        Local(Local),
+        S3(S3),
+    }
+
+    #[derive(setty::Config)]
+    struct Local {
+        path: String,
+    }
+
+    #[derive(setty::Config)]
+    struct S3 {
+        bucket: String,
+    }
+
+    let config = setty::Config::<Cfg>::new();
+    let extract = |v| {
+        setty::Config::<Cfg>::new()
+            .with_source(v)
+            .extract()
+            .unwrap()
+    };
+
+    let local_a = extract(serde_json::json!({"storage": {"kind": "Local", "path": "a"}}));
+    let local_b = extract(serde_json::json!({"storage": {"kind": "Local", "path": "b"}}));
+    let s3 = extract(serde_json::json!({"storage": {"kind": "S3", "bucket": "b"}}));
+
+    // Field of the same variant
+    let diff = config.diff(&local_a, &local_b).unwrap();
+    pretty_assertions::assert_eq!(diff.to_string(), r#"~ storage.path: "a" -> "b""#);
+
+    // Variant switch
+    let diff = config.diff(&local_a, &s3).unwrap();
+    assert!(
+        diff.to_string()
+            .starts_with("~ storage: variant Local -> S3 {"),
+        "{diff}"
+    );
+    assert!(
+        matches!(
+            &diff.changes[0].kind,
+            ChangeKind::VariantChanged { old_variant, new_variant, .. }
+                if old_variant == "Local" && new_variant == "S3"
+        ),
+        "{diff:?}"
+    );
+    pretty_assertions::assert_eq!(
+        diff.to_json_patch(),
+        serde_json::json!([{
+            "op": "replace",
+            "path": "/storage",
+            "value": {"kind": "S3", "bucket": "b"},
+        }])
+    );
+
+    // Without schema the tag is just another field
+    let diff = setty::diff(
+        &serde_json::json!({"kind": "Local", "path": "a"}),
+        &serde_json::json!({"kind": "S3", "bucket": "b"}),
+    );
+    assert_eq!(diff.changes.len(), 3, "{diff}");
+
+    // Optional value removed
+    let none = extract(serde_json::json!({}));
+    let diff = config.diff(&s3, &none).unwrap();
+    assert!(
+        matches!(&diff.changes[..], [c] if c.path == "storage" && matches!(c.kind, ChangeKind::Removed(_))),
+        "{diff:?}"
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
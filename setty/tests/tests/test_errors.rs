@@ -0,0 +1,878 @@
+#![cfg(feature = "derive-deserialize")]
+#![cfg(feature = "fmt-yaml")]
+
+use setty::errors::ReadError;
+use setty::format::Yaml;
+use setty::source::File;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(setty::Config)]
+struct Cfg {
+    #[config(default)]
+    server: Server,
+}
+
+#[derive(setty::Config, setty::Default)]
+struct Server {
+    #[config(default)]
+    host: String,
+    #[config(default)]
+    port: u16,
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_errors_invalid_type_location() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.yaml");
+
+    std::fs::write(
+        &path,
+        indoc::indoc!(
+            "
+            # Server config
+            server:
+              host: localhost
+              port: eighty
+            "
+        ),
+    )
+    .unwrap();
+
+    let err = setty::Config::<Cfg>::new()
+        .with_source(File::<Yaml>::new(&path))
+        .with_source(serde_json::json!({"server": {"host": "example.com"}}))
+        .extract()
+        .unwrap_err();
+
+    pretty_assertions::assert_eq!(
+        err.to_string(),
+        format!(
+            indoc::indoc!(
+                r#"
+                Invalid type at `server.port`: expected u16, found string "eighty" in {}:4:3
+                  |
+                4 |   port: eighty
+                  |   ^"#
+            ),
+            path.display()
+        )
+    );
+
+    let ReadError::InvalidType {
+        path: key,
+        expected,
+        found,
+        location,
+    } = err
+    else {
+        panic!("Unexpected error: {err:?}");
+    };
+    assert_eq!(key, "server.port");
+    assert_eq!(expected, "u16");
+    assert_eq!(found, r#"string "eighty""#);
+
+    let location = location.unwrap();
+    assert_eq!(location.source_name, path.display().to_string());
+    assert_eq!((location.line, location.col), (Some(4), Some(3)));
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_errors_unknown_field_location() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.yaml");
+
+    std::fs::write(&path, "server:\n  hots: localhost\n").unwrap();
+
+    let err = setty::Config::<Cfg>::new()
+        .with_source(File::<Yaml>::new(&path))
+        .extract()
+        .unwrap_err();
+
+    let ReadError::UnknownField {
+        path: key,
+        expected,
+        location,
+        ..
+    } = &err
+    else {
+        panic!("Unexpected error: {err:?}");
+    };
+    assert_eq!(key, "server.hots");
+    assert_eq!(expected, &["host", "port"]);
+
+    let location = location.as_ref().unwrap();
+    assert_eq!((location.line, location.col), (Some(2), Some(3)));
+    assert_eq!(location.line_text.as_deref(), Some("  hots: localhost"));
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_errors_location_skips_comments_and_other_parents() {
+    #[derive(setty::Config)]
+    struct Cfg {
+        #[config(default)]
+        client: Server,
+        #[config(default)]
+        server: Server,
+    }
+
+    let tmp = tempfile::tempdir().unwrap();
+
+    let location = |name: &str, text: &str| {
+        let path = tmp.path().join(name);
+        std::fs::write(&path, text).unwrap();
+
+        let source: std::sync::Arc<dyn setty::format::DynFormat> =
+            setty::format::FormatRegistry::with_defaults()
+                .for_path(&path)
+                .unwrap();
+
+        let err = setty::Config::<Cfg>::new()
+            .with_source(File::with_format(&path, source))
+            .extract()
+            .unwrap_err();
+
+        let ReadError::InvalidType {
+            path: key,
+            location,
+            ..
+        } = &err
+        else {
+            panic!("Unexpected error: {err:?}");
+        };
+        assert_eq!(key, "server.port");
+
+        let location = location.as_ref().unwrap();
+        (location.line.unwrap(), location.col.unwrap())
+    };
+
+    assert_eq!(
+        location(
+            "c.yaml",
+            indoc::indoc!(
+                "
+                client:
+                  port: 1
+                server:
+                  # port: 5432 was old value
+                  port: abc
+                "
+            )
+        ),
+        (5, 3)
+    );
+
+    #[cfg(feature = "fmt-json")]
+    assert_eq!(
+        location(
+            "c.json",
+            r#"{"client": {"port": 1}, "server": {"host": "port: 1", "port": "abc"}}"#
+        ),
+        (1, 55)
+    );
+
+    #[cfg(feature = "fmt-toml")]
+    assert_eq!(
+        location(
+            "c.toml",
+            indoc::indoc!(
+                r#"
+                [client]
+                port = 1
+
+                [server]
+                # port = 5432
+                port = "abc"
+                "#
+            )
+        ),
+        (6, 1)
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_errors_location_from_parser() {
+    #[derive(setty::Config)]
+    struct Cfg {
+        #[config(default)]
+        notes: String,
+        #[config(default)]
+        server: Server,
+    }
+
+    let tmp = tempfile::tempdir().unwrap();
+
+    let location = |name: &str, text: &str| {
+        let path = tmp.path().join(name);
+        std::fs::write(&path, text).unwrap();
+
+        let source: std::sync::Arc<dyn setty::format::DynFormat> =
+            setty::format::FormatRegistry::with_defaults()
+                .for_path(&path)
+                .unwrap();
+
+        let err = setty::Config::<Cfg>::new()
+            .with_source(File::with_format(&path, source))
+            .extract()
+            .unwrap_err();
+
+        assert_eq!(err.path(), Some("server.port"));
+        let location = err.location().unwrap();
+        (location.line.unwrap(), location.col.unwrap())
+    };
+
+    // Keys inside of strings are not mistaken for the offending ones
+    assert_eq!(
+        location(
+            "c.yaml",
+            indoc::indoc!(
+                "
+                notes: |
+                  server:
+                    port: 1
+                server:
+                  port: abc
+                "
+            )
+        ),
+        (5, 3)
+    );
+
+    // Later documents take precedence
+    assert_eq!(
+        location(
+            "multi.yaml",
+            indoc::indoc!(
+                "
+                server:
+                  port: 1
+                ---
+                server:
+                  port: abc
+                "
+            )
+        ),
+        (5, 3)
+    );
+
+    #[cfg(feature = "fmt-json")]
+    assert_eq!(
+        location(
+            "c.json",
+            indoc::indoc!(
+                r#"
+                {
+                  "notes": "{\"server\": {\"port\": 1}}",
+                  "server": {"port": "abc"}
+                }
+                "#
+            )
+        ),
+        (3, 14)
+    );
+
+    #[cfg(feature = "fmt-toml")]
+    assert_eq!(
+        location(
+            "c.toml",
+            indoc::indoc!(
+                r#"
+                notes = """
+                [server]
+                port = 1
+                """
+
+                [server]
+                port = "abc"
+                "#
+            )
+        ),
+        (7, 1)
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_errors_syntax_location() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.yaml");
+
+    std::fs::write(&path, "server:\n  host: [localhost\n").unwrap();
+
+    let err = setty::Config::<Cfg>::new()
+        .with_source(File::<Yaml>::new(&path))
+        .extract()
+        .unwrap_err();
+
+    let ReadError::Syntax { location, .. } = &err else {
+        panic!("Unexpected error: {err:?}");
+    };
+    assert_eq!(location.source_name, path.display().to_string());
+    assert_eq!(location.line, Some(3));
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_errors_kinds() {
+    #[derive(setty::Config)]
+    struct Cfg {
+        name: String,
+        #[config(default)]
+        mode: Mode,
+    }
+
+    #[derive(setty::Config, setty::Default)]
+    enum Mode {
+        #[default]
+        Fast,
+        Safe,
+    }
+
+    // Suggests fields that only differ in case
+    let err = setty::Config::<Cfg>::new()
+        .with_source(serde_json::json!({"name": "a", "Name": "b"}))
+        .extract()
+        .unwrap_err();
+
+    assert!(
+        matches!(&err, ReadError::UnknownField { path, suggestions, .. } if path == "Name" && suggestions == &["name"]),
+        "{err:?}"
+    );
+    pretty_assertions::assert_eq!(
+        err.to_string(),
+        "Unknown field `Name` in <raw value>, did you mean `name`?"
+    );
+
+    // Missing field at the root
+    let err = setty::Config::<Cfg>::new()
+        .with_source(serde_json::json!({}))
+        .extract()
+        .unwrap_err();
+
+    assert!(
+        matches!(&err, ReadError::MissingField { path, location: None } if path == "name"),
+        "{err:?}"
+    );
+
+    // Unknown enum variant
+    let err = setty::Config::<Cfg>::new()
+        .with_source(serde_json::json!({"name": "a", "mode": "Slow"}))
+        .extract()
+        .unwrap_err();
+
+    assert!(
+        matches!(&err, ReadError::UnknownVariant { path, variant, expected, .. } if path == "mode" && variant == "Slow" && expected.contains(&"Safe".to_string())),
+        "{err:?}"
+    );
+    assert_eq!(err.path(), Some("mode"));
+    assert_eq!(err.location().unwrap().source_name, "<raw value>");
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_errors_kinds_per_format() {
+    #[derive(setty::Config)]
+    struct Cfg {
+        name: String,
+        #[config(default)]
+        server: Server,
+        #[config(default)]
+        mode: Mode,
+        #[config(default)]
+        level: u8,
+    }
+
+    #[derive(setty::Config, setty::Default)]
+    enum Mode {
+        #[default]
+        Fast,
+        Safe,
+    }
+
+    let tmp = tempfile::tempdir().unwrap();
+
+    // Messages of serde errors that are turned into structured errors don't depend on
+    // the format, as all sources are merged into a single value before deserializing
+    let kinds = |name: &str, text: &str| {
+        let path = tmp.path().join(name);
+        std::fs::write(&path, text).unwrap();
+
+        let format: std::sync::Arc<dyn setty::format::DynFormat> =
+            setty::format::FormatRegistry::with_defaults()
+                .for_path(&path)
+                .unwrap();
+
+        let err = setty::Config::<Cfg>::new()
+            .with_source(File::with_format(&path, format))
+            .with_all_errors(true)
+            .extract()
+            .unwrap_err();
+
+        let ReadError::Multiple(errors) = &err else {
+            panic!("Unexpected error: {err:?}");
+        };
+
+        // Validation can't run without the required field
+        #[cfg(feature = "derive-validate")]
+        let errors = match errors.split_last() {
+            Some((ReadError::ValidationSkipped, errors)) => errors,
+            _ => panic!("Unexpected error: {err:?}"),
+        };
+
+        let mut kinds: Vec<_> = errors
+            .iter()
+            .map(|e| {
+                let kind = match e {
+                    ReadError::UnknownField { suggestions, .. } => {
+                        assert_eq!(suggestions, &["server.host"]);
+                        "unknown field"
+                    }
+                    ReadError::InvalidType {
+                        expected, found, ..
+                    } => {
+                        assert_eq!(
+                            (expected.as_str(), found.as_str()),
+                            ("u16", r#"string "eighty""#)
+                        );
+                        "invalid type"
+                    }
+                    ReadError::InvalidValue {
+                        expected, found, ..
+                    } => {
+                        assert_eq!(
+                            (expected.as_str(), found.as_str()),
+                            ("u8", "integer `1000`")
+                        );
+                        "invalid value"
+                    }
+                    ReadError::UnknownVariant {
+                        variant, expected, ..
+                    } => {
+                        assert_eq!(
+                            (variant.as_str(), expected.as_slice()),
+                            ("Slow", ["Fast".to_string(), "Safe".to_string()].as_slice())
+                        );
+                        "unknown variant"
+                    }
+                    ReadError::MissingField { .. } => "missing field",
+                    _ => panic!("Unexpected error: {e:?}"),
+                };
+                (e.path().unwrap().to_string(), kind)
+            })
+            .collect();
+        kinds.sort();
+        kinds
+    };
+
+    let expected = [
+        ("level".to_string(), "invalid value"),
+        ("mode".to_string(), "unknown variant"),
+        ("name".to_string(), "missing field"),
+        ("server.hots".to_string(), "unknown field"),
+        ("server.port".to_string(), "invalid type"),
+    ];
+
+    pretty_assertions::assert_eq!(
+        kinds(
+            "c.yaml",
+            "server:\n  hots: a\n  port: eighty\nmode: Slow\nlevel: 1000\n"
+        ),
+        expected
+    );
+
+    #[cfg(feature = "fmt-toml")]
+    pretty_assertions::assert_eq!(
+        kinds(
+            "c.toml",
+            "mode = \"Slow\"\nlevel = 1000\n[server]\nhots = \"a\"\nport = \"eighty\"\n"
+        ),
+        expected
+    );
+
+    #[cfg(feature = "fmt-json")]
+    pretty_assertions::assert_eq!(
+        kinds(
+            "c.json",
+            r#"{"server": {"hots": "a", "port": "eighty"}, "mode": "Slow", "level": 1000}"#
+        ),
+        expected
+    );
+
+    #[cfg(feature = "fmt-json5")]
+    pretty_assertions::assert_eq!(
+        kinds(
+            "c.json5",
+            "{server: {hots: 'a', port: 'eighty'}, mode: 'Slow', level: 1000, // comment\n}"
+        ),
+        expected
+    );
+
+    #[cfg(feature = "fmt-ron")]
+    pretty_assertions::assert_eq!(
+        kinds(
+            "c.ron",
+            r#"(server: (hots: "a", port: "eighty"), mode: Slow, level: 1000)"#
+        ),
+        expected
+    );
+
+    // Strings of untyped formats are converted to numbers using the schema
+    #[cfg(feature = "fmt-ini")]
+    #[cfg(feature = "derive-jsonschema")]
+    pretty_assertions::assert_eq!(
+        kinds(
+            "c.ini",
+            "mode = Slow\nlevel = 1000\n[server]\nhots = a\nport = eighty\n"
+        ),
+        expected
+    );
+
+    #[cfg(feature = "fmt-properties")]
+    #[cfg(feature = "derive-jsonschema")]
+    pretty_assertions::assert_eq!(
+        kinds(
+            "c.properties",
+            "mode=Slow\nlevel=1000\nserver.hots=a\nserver.port=eighty\n"
+        ),
+        expected
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_errors_all_errors() {
+    #[derive(setty::Config)]
+    struct Cfg {
+        name: String,
+        #[config(default)]
+        server: Server,
+        #[config(default)]
+        algo: Option<Algo>,
+    }
+
+    #[derive(setty::Config)]
+    #[serde(tag = "kind")]
+    enum Algo {
+        Fast(Params),
+        Safe(Params),
+    }
+
+    #[derive(setty::Config)]
+    struct Params {
+        rounds: u8,
+    }
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.yaml");
+
+    std::fs::write(
+        &path,
+        indoc::indoc!(
+            "
+            server:
+              hots: localhost
+              port: eighty
+            algo:
+              kind: fast
+              rounds: 1000
+            "
+        ),
+    )
+    .unwrap();
+
+    let err = setty::Config::<Cfg>::new()
+        .with_source(File::<Yaml>::new(&path))
+        .with_all_errors(true)
+        .extract()
+        .unwrap_err();
+
+    let ReadError::Multiple(errors) = &err else {
+        panic!("Unexpected error: {err:?}");
+    };
+
+    // Validation can't run without the required field
+    #[cfg(feature = "derive-validate")]
+    let errors = match errors.split_last() {
+        Some((ReadError::ValidationSkipped, errors)) => errors,
+        _ => panic!("Unexpected error: {err:?}"),
+    };
+
+    let summary: Vec<_> = errors
+        .iter()
+        .map(|e| {
+            let line = e.location().and_then(|l| l.line);
+            (e.path().unwrap(), line)
+        })
+        .collect();
+
+    // Errors are reported in order of fields, with missing fields last
+    #[cfg(feature = "preserve-order")]
+    pretty_assertions::assert_eq!(
+        summary,
+        [
+            ("server.hots", Some(2)),
+            ("server.port", Some(3)),
+            ("algo.rounds", Some(6)),
+            ("name", None),
+        ]
+    );
+
+    // Errors are reported in alphabetical order of fields, with missing fields last
+    #[cfg(not(feature = "preserve-order"))]
+    pretty_assertions::assert_eq!(
+        summary,
+        [
+            ("algo.rounds", Some(6)),
+            ("server.hots", Some(2)),
+            ("server.port", Some(3)),
+            ("name", None),
+        ]
+    );
+
+    let error_at = |p: &str| errors.iter().find(|e| e.path() == Some(p)).unwrap();
+
+    assert!(
+        matches!(error_at("server.hots"), ReadError::UnknownField { suggestions, .. } if suggestions == &["server.host"]),
+        "{err:?}"
+    );
+    assert!(
+        matches!(error_at("server.port"), ReadError::InvalidType { expected, .. } if expected == "u16"),
+        "{err:?}"
+    );
+    assert!(
+        matches!(error_at("algo.rounds"), ReadError::InvalidValue { expected, found, .. } if expected == "u8" && found == "integer `1000`"),
+        "{err:?}"
+    );
+    assert!(
+        matches!(error_at("name"), ReadError::MissingField { .. }),
+        "{err:?}"
+    );
+
+    #[cfg(feature = "preserve-order")]
+    let first = format!(
+        "Unknown field `server.hots` in {}:2:3, did you mean `server.host`?\n  ",
+        path.display()
+    );
+    #[cfg(not(feature = "preserve-order"))]
+    let first = format!(
+        "Invalid value at `algo.rounds`: expected u8, found integer `1000` in {}:6:3\n  ",
+        path.display()
+    );
+    assert!(
+        err.to_string().starts_with(&format!(
+            "Found {} errors in config:\n- {first}",
+            if cfg!(feature = "derive-validate") {
+                5
+            } else {
+                4
+            }
+        )),
+        "{err}"
+    );
+
+    // Valid config passes
+    setty::Config::<Cfg>::new()
+        .with_source(serde_json::json!({"name": "a", "algo": {"kind": "safe", "rounds": 3}}))
+        .with_all_errors(true)
+        .extract()
+        .unwrap();
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_errors_all_errors_enum_case() {
+    #[derive(setty::Config)]
+    struct Cfg {
+        #[config(default)]
+        read: Mode,
+        #[config(default)]
+        write: Mode,
+    }
+
+    #[derive(setty::Config, setty::Default)]
+    enum Mode {
+        #[default]
+        Fast,
+        Safe,
+    }
+
+    let res = setty::Config::<Cfg>::new()
+        .with_source(serde_json::json!({"read": "safe", "write": "safe"}))
+        .with_all_errors(true)
+        .extract();
+
+    // Same as serde, variants are accepted in any case only with `case-enums-any`
+    #[cfg(feature = "case-enums-any")]
+    assert!(matches!(res.unwrap().write, Mode::Safe));
+
+    #[cfg(not(feature = "case-enums-any"))]
+    {
+        let err = res.err().unwrap();
+        let ReadError::Multiple(errors) = &err else {
+            panic!("Unexpected error: {err:?}");
+        };
+        let mut paths: Vec<_> = errors.iter().map(|e| e.path().unwrap()).collect();
+        paths.sort();
+        pretty_assertions::assert_eq!(paths, ["read", "write"]);
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "derive-validate")]
+#[test]
+fn test_errors_all_validation_errors() {
+    #[derive(setty::Config)]
+    struct Cfg {
+        #[config(validate(length(min = 3)))]
+        name: String,
+        #[config(validate(range(min = 1)))]
+        port: u16,
+    }
+
+    let err = setty::Config::<Cfg>::new()
+        .with_source(serde_json::json!({"name": "a", "port": 0}))
+        .with_all_errors(true)
+        .extract()
+        .unwrap_err();
+
+    pretty_assertions::assert_eq!(
+        err.to_string(),
+        indoc::indoc!(
+            "
+            Found 2 errors in config:
+            - Validation failed at `name`: length (min = 3) in <raw value>
+            - Validation failed at `port`: range (min = 1) in <raw value>"
+        )
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "derive-validate")]
+#[test]
+fn test_errors_all_errors_with_validation() {
+    static LOWERCASE: std::sync::LazyLock<regex::Regex> =
+        std::sync::LazyLock::new(|| regex::Regex::new("^[a-z]+$").unwrap());
+
+    fn no_spaces(value: &str) -> Result<(), validator::ValidationError> {
+        if value.contains(' ') {
+            return Err(validator::ValidationError::new("no_spaces"));
+        }
+        Ok(())
+    }
+
+    #[derive(setty::Config)]
+    struct Cfg {
+        #[config(default, validate(regex(path = *LOWERCASE)))]
+        code: String,
+        #[config(validate(length(min = 3)))]
+        name: String,
+        #[config(default, validate(custom(function = "no_spaces")))]
+        tag: String,
+        #[config(default)]
+        port: u16,
+    }
+
+    // Deserialization, schema and validation errors are reported together
+    let err = setty::Config::<Cfg>::new()
+        .with_source(serde_json::json!({
+            "code": "ABC",
+            "name": "a",
+            "tag": "a b",
+            "port": "eighty",
+        }))
+        .with_all_errors(true)
+        .extract()
+        .unwrap_err();
+
+    pretty_assertions::assert_eq!(
+        err.to_string(),
+        indoc::indoc!(
+            r#"
+            Found 4 errors in config:
+            - Invalid value at `code`: expected string matching `^[a-z]+$`, found string "ABC" in <raw value>
+            - Invalid value at `name`: expected string of length at least 3, found string "a" in <raw value>
+            - Invalid type at `port`: expected u16, found string "eighty" in <raw value>
+            - Validation failed at `tag`: no_spaces in <raw value>"#
+        )
+    );
+
+    // Validation is skipped when the config can't be deserialized without the invalid values
+    let err = setty::Config::<Cfg>::new()
+        .with_source(serde_json::json!({"tag": "a b", "port": "eighty"}))
+        .with_all_errors(true)
+        .extract()
+        .unwrap_err();
+
+    pretty_assertions::assert_eq!(
+        err.to_string(),
+        indoc::indoc!(
+            r#"
+            Found 3 errors in config:
+            - Invalid type at `port`: expected u16, found string "eighty" in <raw value>
+            - Missing required field `name`
+            - Validation rules were not checked due to the errors above"#
+        )
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_errors_suggestions() {
+    #[derive(setty::Config)]
+    struct Cfg {
+        #[config(default)]
+        server: Server,
+        #[config(default)]
+        schema_name: String,
+    }
+
+    let suggestions = |value: serde_json::Value| {
+        let err = setty::Config::<Cfg>::new()
+            .with_source(value)
+            .extract()
+            .unwrap_err();
+        match err {
+            ReadError::UnknownField { suggestions, .. } => suggestions,
+            err => panic!("Unexpected error: {err:?}"),
+        }
+    };
+
+    // Typo
+    assert_eq!(
+        suggestions(serde_json::json!({"server": {"prot": 80}})),
+        ["server.port"]
+    );
+
+    // Wrong case convention
+    assert_eq!(
+        suggestions(serde_json::json!({"schemaName": "a"})),
+        ["schema_name"]
+    );
+
+    // Key on a wrong level
+    assert_eq!(
+        suggestions(serde_json::json!({"host": "localhost"})),
+        ["server.host"]
+    );
+
+    // Nothing similar
+    assert!(suggestions(serde_json::json!({"server": {"database": "a"}})).is_empty());
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
@@ -45,6 +45,49 @@ fn test_format_json() {
 
 /////////////////////////////////////////////////////////////////////////////////////////
 
+#[cfg(feature = "fmt-json5")]
+#[test]
+fn test_format_json5() {
+    let cfg: MyConfig = setty::Config::new()
+        .with_source(setty::source::RawData::<setty::format::Json5>::new(
+            indoc::indoc!(
+                r#"
+                // Comments are allowed
+                {
+                    database: {
+                        kind: "Postgres",
+                        schema_name: 'my_schema',
+                        host: "my_host", // Trailing commas too
+                    },
+                    /* Block comment */
+                    "encryption": {
+                        "algo": "Rsa",
+                        "key": "secret",
+                    },
+                }
+                "#,
+            ),
+        ))
+        .extract()
+        .unwrap();
+
+    assert_eq!(
+        cfg,
+        MyConfig {
+            database: DatabaseConfig::Postgres(PostgresDatabaseConfig {
+                schema_name: "my_schema".into(),
+                host: "my_host".into()
+            }),
+            encryption: Some(EncryptionConfig {
+                key: "secret".into(),
+                algo: EncryptionAlgo::Rsa
+            }),
+        }
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
 #[cfg(feature = "fmt-yaml")]
 #[test]
 fn test_format_yaml() {
@@ -160,3 +203,905 @@ fn test_format_toml() {
 }
 
 /////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "fmt-ini")]
+#[test]
+fn test_format_ini() {
+    let cfg: MyConfig = setty::Config::new()
+        .with_source(setty::source::RawData::<setty::format::Ini>::new(
+            indoc::indoc!(
+                r#"
+                ; Comments are allowed
+                [database]
+                kind = Postgres
+                schema_name = my_schema
+                host = my_host
+
+                [encryption]
+                algo = Rsa
+                key = secret
+                "#,
+            ),
+        ))
+        .extract()
+        .unwrap();
+
+    assert_eq!(
+        cfg,
+        MyConfig {
+            database: DatabaseConfig::Postgres(PostgresDatabaseConfig {
+                schema_name: "my_schema".into(),
+                host: "my_host".into()
+            }),
+            encryption: Some(EncryptionConfig {
+                key: "secret".into(),
+                algo: EncryptionAlgo::Rsa
+            }),
+        }
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "fmt-ini")]
+#[test]
+fn test_format_ini_coercion_and_roundtrip() {
+    use setty::format::{Format, Ini, IniWithSeparator};
+
+    #[derive(setty::Config)]
+    struct Cfg {
+        name: String,
+        version: String,
+        prefix: String,
+        #[config(default)]
+        database: Database,
+    }
+
+    #[derive(setty::Config, setty::Default)]
+    struct Database {
+        #[config(default)]
+        port: u16,
+        #[config(default)]
+        enabled: bool,
+        #[config(default)]
+        hosts: Vec<String>,
+        timeout: Option<u32>,
+        #[config(default)]
+        pool: Pool,
+    }
+
+    #[derive(setty::Config, setty::Default)]
+    struct Pool {
+        #[config(default)]
+        size: u32,
+    }
+
+    let data = indoc::indoc!(
+        r#"
+        name = 12345
+        version = 1.0
+        prefix = " > "
+
+        [database]
+        port = 5432
+        enabled = true
+        hosts = ["a", "b"]
+        timeout =
+
+        [database.pool]
+        size = 10
+        "#
+    );
+
+    // Values are read as strings
+    let value: setty::Value = Ini::deserialize(data).unwrap();
+    pretty_assertions::assert_eq!(value["name"], "12345");
+    pretty_assertions::assert_eq!(value["database"]["port"], "5432");
+
+    // And converted to the types of the fields
+    let cfg: Cfg = setty::Config::new()
+        .with_source(setty::source::RawData::<Ini>::new(data))
+        .extract()
+        .unwrap();
+
+    assert_eq!(cfg.name, "12345");
+    assert_eq!(cfg.version, "1.0");
+    assert_eq!(cfg.prefix, " > ");
+    assert_eq!(cfg.database.port, 5432);
+    assert!(cfg.database.enabled);
+    assert_eq!(cfg.database.hosts, ["a", "b"]);
+    assert_eq!(cfg.database.timeout, None);
+    assert_eq!(cfg.database.pool.size, 10);
+
+    // Values that can't be converted are reported with their types
+    let err = setty::Config::<Cfg>::new()
+        .with_source(setty::source::RawData::<Ini>::new(
+            "name = a\nversion = b\nprefix = c\n[database]\nport = eighty\n",
+        ))
+        .extract()
+        .err()
+        .unwrap();
+    assert!(
+        matches!(&err, setty::errors::ReadError::InvalidType { path, .. } if path == "database.port"),
+        "{err:?}"
+    );
+
+    // Serialization only quotes strings that would be changed when reading
+    let value = serde_json::json!({
+        "name": "12345",
+        "prefix": " > ",
+        "quoted": "'x'",
+        "escaped": "a\\b\nc",
+        "database": {
+            "port": 5432,
+            "enabled": false,
+            "hosts": ["a", "b"],
+            "pool": {
+                "size": 10,
+            },
+        },
+    });
+
+    let ini = Ini::serialize(&value).unwrap();
+    let back: setty::Value = Ini::deserialize(&ini).unwrap();
+    pretty_assertions::assert_eq!(
+        back,
+        serde_json::json!({
+            "name": "12345",
+            "prefix": " > ",
+            "quoted": "'x'",
+            "escaped": "a\\b\nc",
+            "database": {
+                "port": "5432",
+                "enabled": "false",
+                "hosts": r#"["a","b"]"#,
+                "pool": {
+                    "size": "10",
+                },
+            },
+        })
+    );
+
+    // Custom section separator
+    let ini = IniWithSeparator::<'/'>::serialize(&value).unwrap();
+    assert!(ini.contains("[database/pool]"), "{ini}");
+    let back_sep: setty::Value = IniWithSeparator::<'/'>::deserialize(&ini).unwrap();
+    pretty_assertions::assert_eq!(back_sep, back);
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "fmt-ron")]
+#[test]
+fn test_format_ron() {
+    let cfg: MyConfig = setty::Config::new()
+        .with_source(setty::source::RawData::<setty::format::Ron>::new(
+            indoc::indoc!(
+                r#"
+                #![enable(implicit_some)]
+                (
+                    // Enum variants are named structs
+                    database: Postgres(
+                        schema_name: "my_schema",
+                        host: r"my_host",
+                    ),
+                    /* Options can be explicit */
+                    encryption: Some((
+                        algo: Rsa,
+                        key: "secret",
+                    )),
+                )
+                "#,
+            ),
+        ))
+        .extract()
+        .unwrap();
+
+    assert_eq!(
+        cfg,
+        MyConfig {
+            database: DatabaseConfig::Postgres(PostgresDatabaseConfig {
+                schema_name: "my_schema".into(),
+                host: "my_host".into()
+            }),
+            encryption: Some(EncryptionConfig {
+                key: "secret".into(),
+                algo: EncryptionAlgo::Rsa
+            }),
+        }
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "fmt-ron")]
+#[test]
+fn test_format_ron_roundtrip() {
+    use setty::format::{Format, Ron};
+
+    let cfg = MyConfig {
+        database: DatabaseConfig::Postgres(PostgresDatabaseConfig {
+            schema_name: "my_schema".into(),
+            host: "my_host".into(),
+        }),
+        encryption: None,
+    };
+
+    // Typed values are written as structs with explicit tags
+    let ron = Ron::serialize(&cfg).unwrap();
+    pretty_assertions::assert_eq!(
+        ron,
+        indoc::indoc!(
+            r#"
+            (
+                database: (
+                    kind: "Postgres",
+                    schema_name: "my_schema",
+                    host: "my_host",
+                ),
+            )
+            "#
+        )
+    );
+    assert_eq!(Ron::deserialize::<MyConfig>(&ron).unwrap(), cfg);
+
+    let value = serde_json::json!({
+        "unit": {"kind": "Serial"},
+        "kebab-case": "a\\b\n\"c\"",
+        "nums": [1, -2, 0.5, u64::MAX],
+        "empty": {},
+        "tuple": [],
+        "null": null,
+        "named": {"kind": "Named", "true": {"kind": "Serial", "a": [{"b": null}]}},
+    });
+
+    let ron = Ron::serialize(&value).unwrap();
+    let back: setty::Value = Ron::deserialize(&ron).unwrap();
+    pretty_assertions::assert_eq!(back, value);
+
+    // Names of structs and variants are kept in externally tagged representation
+    let back: setty::Value = Ron::deserialize(indoc::indoc!(
+        r#"
+        Config(
+            a: 0x1F,
+            b: 1_000u32,
+            c: 1e3,
+            d: '\n',
+            e: (1, "x"),
+            f: Some(Postgres((host: "x"))),
+            g: [Serial, Serial(), None],
+        )
+        "#
+    ))
+    .unwrap();
+    pretty_assertions::assert_eq!(
+        back,
+        serde_json::json!({"Config": {
+            "a": 31,
+            "b": 1000,
+            "c": 1000.0,
+            "d": "\n",
+            "e": [1, "x"],
+            "f": {"Postgres": {"host": "x"}},
+            "g": ["Serial", {"Serial": null}, null],
+        }})
+    );
+
+    let err = Ron::deserialize::<setty::Value>("(\n    a: 1,\n    b: @,\n)").unwrap_err();
+    assert_eq!(
+        setty::format::Ron::error_position(&err, ""),
+        Some((3, 8)),
+        "{err}"
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "fmt-ron")]
+#[test]
+fn test_format_ron_named_structs() {
+    #[derive(setty::Config)]
+    struct Config {
+        name: String,
+        database: DatabaseConfig,
+        #[config(default)]
+        replicas: Vec<DatabaseConfig>,
+        encryption: Option<EncryptionConfig>,
+    }
+
+    let cfg: Config = setty::Config::new()
+        .with_source(setty::source::RawData::<setty::format::Ron>::new(
+            indoc::indoc!(
+                r#"
+                Config(
+                    name: "Config",
+                    database: Postgres(schema_name: "a", host: "x"),
+                    replicas: [
+                        Sqlite(database_path: "b"),
+                        (kind: "Sqlite", database_path: "c"),
+                    ],
+                    encryption: Some(EncryptionConfig(algo: Rsa, key: "secret")),
+                )
+                "#,
+            ),
+        ))
+        .extract()
+        .unwrap();
+
+    pretty_assertions::assert_eq!(cfg.name, "Config");
+    pretty_assertions::assert_eq!(
+        cfg.database,
+        DatabaseConfig::Postgres(PostgresDatabaseConfig {
+            schema_name: "a".into(),
+            host: "x".into(),
+        })
+    );
+    pretty_assertions::assert_eq!(
+        cfg.replicas,
+        [
+            DatabaseConfig::Sqlite(SqliteDatabaseConfig {
+                database_path: "b".into(),
+            }),
+            DatabaseConfig::Sqlite(SqliteDatabaseConfig {
+                database_path: "c".into(),
+            }),
+        ]
+    );
+    pretty_assertions::assert_eq!(
+        cfg.encryption,
+        Some(EncryptionConfig {
+            key: "secret".into(),
+            algo: EncryptionAlgo::Rsa,
+        })
+    );
+
+    // Names are mapped when editing files too
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.ron");
+    std::fs::write(
+        &path,
+        r#"Config(name: "a", database: Sqlite(database_path: "b"))"#,
+    )
+    .unwrap();
+
+    let fig = setty::Config::<Config>::new()
+        .with_source(setty::source::File::<setty::format::Ron>::new(&path));
+    fig.set_value::<setty::format::Ron>("name", "b", &path)
+        .unwrap();
+
+    let cfg = fig.extract().unwrap();
+    pretty_assertions::assert_eq!(cfg.name, "b");
+    pretty_assertions::assert_eq!(
+        cfg.database,
+        DatabaseConfig::Sqlite(SqliteDatabaseConfig {
+            database_path: "b".into(),
+        })
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "fmt-properties")]
+#[test]
+fn test_format_properties() {
+    let cfg: MyConfig = setty::Config::new()
+        .with_source(setty::source::RawData::<setty::format::Properties>::new(
+            indoc::indoc!(
+                r"
+                # Comment
+                ! Another comment
+                database.kind = Postgres
+                database.schema_name: my_\
+                                      schema
+                database.host my_host
+                encryption.algo=Rsa
+                encryption.key=secret
+                ",
+            ),
+        ))
+        .extract()
+        .unwrap();
+
+    assert_eq!(
+        cfg,
+        MyConfig {
+            database: DatabaseConfig::Postgres(PostgresDatabaseConfig {
+                schema_name: "my_schema".into(),
+                host: "my_host".into()
+            }),
+            encryption: Some(EncryptionConfig {
+                key: "secret".into(),
+                algo: EncryptionAlgo::Rsa
+            }),
+        }
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "fmt-properties")]
+#[test]
+fn test_format_properties_conversion_and_roundtrip() {
+    use setty::format::{Format, Properties};
+
+    #[derive(setty::Config)]
+    struct Cfg {
+        name: String,
+        version: String,
+        #[config(default)]
+        database: Database,
+    }
+
+    #[derive(setty::Config, setty::Default)]
+    struct Database {
+        #[config(default)]
+        port: u16,
+        #[config(default)]
+        enabled: bool,
+        #[config(default)]
+        hosts: Vec<String>,
+        timeout: Option<u32>,
+    }
+
+    let data = indoc::indoc!(
+        r#"
+        name=12345
+        version=2.0
+        database.port=5432
+        database.enabled=true
+        database.hosts=["a", "b"]
+        database.timeout=
+        "#
+    );
+
+    // Values are read as strings
+    let value: setty::Value = Properties::deserialize(data).unwrap();
+    pretty_assertions::assert_eq!(value["version"], "2.0");
+    pretty_assertions::assert_eq!(value["database"]["port"], "5432");
+
+    // And converted to the types of the fields
+    let cfg: Cfg = setty::Config::new()
+        .with_source(setty::source::RawData::<Properties>::new(data))
+        .extract()
+        .unwrap();
+
+    assert_eq!(cfg.name, "12345");
+    assert_eq!(cfg.version, "2.0");
+    assert_eq!(cfg.database.port, 5432);
+    assert!(cfg.database.enabled);
+    assert_eq!(cfg.database.hosts, ["a", "b"]);
+    assert_eq!(cfg.database.timeout, None);
+
+    // Values are written without quotes, escaping only what `.properties` requires
+    let value = serde_json::json!({
+        "name": " foo bar",
+        "version": "1.0",
+        "quoted": "\"x\"",
+        "escaped": "a\\b\nc=d",
+        "unicode": "😀ü",
+        "key with spaces=:": "#!",
+        "database": {
+            "port": 5432,
+            "enabled": false,
+            "hosts": ["a", "b"],
+            "pool": {
+                "size": 10,
+            },
+        },
+    });
+
+    let props = Properties::serialize(&value).unwrap();
+    assert!(props.contains("version=1.0\n"), "{props}");
+    assert!(props.contains("quoted=\"x\"\n"), "{props}");
+    assert!(props.contains("database.pool.size=10\n"), "{props}");
+
+    let back: setty::Value = Properties::deserialize(&props).unwrap();
+    pretty_assertions::assert_eq!(
+        back,
+        serde_json::json!({
+            "name": " foo bar",
+            "version": "1.0",
+            "quoted": "\"x\"",
+            "escaped": "a\\b\nc=d",
+            "unicode": "😀ü",
+            "key with spaces=:": "#!",
+            "database": {
+                "port": "5432",
+                "enabled": "false",
+                "hosts": r#"["a","b"]"#,
+                "pool": {
+                    "size": "10",
+                },
+            },
+        })
+    );
+
+    let back: setty::Value =
+        Properties::deserialize("emoji=\\ud83d\\ude00\nslash=a\\\\\n").unwrap();
+    pretty_assertions::assert_eq!(back, serde_json::json!({"emoji": "😀", "slash": "a\\"}));
+
+    let err = Properties::deserialize::<setty::Value>("a=1\n\na.b=2").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Key `a.b` conflicts with a value of key `a` at line 3"
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "fmt-properties")]
+#[test]
+fn test_format_properties_dotted_keys() {
+    use setty::format::Properties;
+
+    #[derive(setty::Config)]
+    struct Cfg {
+        #[config(default, combine(merge))]
+        labels: std::collections::BTreeMap<String, String>,
+    }
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.properties");
+    std::fs::write(&path, "labels.app\\.kubernetes\\.io/name=foo\n").unwrap();
+
+    let fig =
+        setty::Config::<Cfg>::new().with_source(setty::source::File::<Properties>::new(&path));
+
+    fig.set_value::<Properties>(r#"labels."x.y""#, "v", &path)
+        .unwrap();
+
+    pretty_assertions::assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        indoc::indoc!(
+            r"
+            labels.app\.kubernetes\.io/name=foo
+            labels.x\.y=v
+            "
+        ),
+    );
+
+    let cfg = fig.extract().unwrap();
+    pretty_assertions::assert_eq!(
+        cfg.labels,
+        [
+            ("app.kubernetes.io/name".to_string(), "foo".to_string()),
+            ("x.y".to_string(), "v".to_string()),
+        ]
+        .into()
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "fmt-hcl")]
+#[test]
+fn test_format_hcl() {
+    let cfg: MyConfig = setty::Config::new()
+        .with_source(setty::source::RawData::<setty::format::Hcl>::new(
+            indoc::indoc!(
+                r#"
+                # Blocks are nested objects
+                database {
+                    kind        = "Postgres"
+                    schema_name = "my_schema"
+                    host        = "my_${"host"}"
+                }
+
+                encryption {
+                    algo = "Rsa"
+                    key  = "secret"
+                }
+                "#,
+            ),
+        ))
+        .extract()
+        .unwrap();
+
+    assert_eq!(
+        cfg,
+        MyConfig {
+            database: DatabaseConfig::Postgres(PostgresDatabaseConfig {
+                schema_name: "my_schema".into(),
+                host: "my_host".into()
+            }),
+            encryption: Some(EncryptionConfig {
+                key: "secret".into(),
+                algo: EncryptionAlgo::Rsa
+            }),
+        }
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "fmt-hcl")]
+#[test]
+fn test_format_hcl_blocks_and_roundtrip() {
+    use setty::format::{Format, Hcl};
+
+    #[derive(setty::Config)]
+    struct Cfg {
+        #[config(default, combine(merge))]
+        plugins: Vec<Plugin>,
+        #[config(default, combine(merge))]
+        servers: std::collections::BTreeMap<String, Server>,
+    }
+
+    #[derive(setty::Config)]
+    struct Plugin {
+        name: String,
+    }
+
+    #[derive(setty::Config)]
+    struct Server {
+        port: u16,
+    }
+
+    // Repeated blocks are arrays and labels are nested objects, so they
+    // combine across sources just like in other formats
+    let cfg: Cfg = setty::Config::new()
+        .with_source(setty::source::RawData::<Hcl>::new(indoc::indoc!(
+            r#"
+            plugins { name = "a" }
+            plugins { name = "b" }
+            servers "http" { port = 80 }
+            "#
+        )))
+        .with_source(setty::source::RawData::<Hcl>::new(indoc::indoc!(
+            r#"
+            plugins = [{ name = "c" }]
+            servers "https" { port = 1 + 442 }
+            "#
+        )))
+        .extract()
+        .unwrap();
+
+    assert_eq!(
+        cfg.plugins
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect::<Vec<_>>(),
+        ["a", "b", "c"]
+    );
+    assert_eq!(cfg.servers["http"].port, 80);
+    assert_eq!(cfg.servers["https"].port, 443);
+
+    let value = serde_json::json!({
+        "name": "${not_a_template}",
+        "database": {
+            "kind": "Postgres",
+            "port": 5432,
+            "ratio": 0.5,
+            "hosts": ["a", "b"],
+            "labels": {"not an ident": true},
+            "empty": {},
+            "none": null,
+        },
+    });
+
+    let hcl = Hcl::serialize(&value).unwrap();
+    assert!(hcl.contains("database {"), "{hcl}");
+    let back: setty::Value = Hcl::deserialize(&hcl).unwrap();
+    pretty_assertions::assert_eq!(back, value);
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "fmt-yaml")]
+#[test]
+fn test_format_yaml_tags() {
+    use setty::format::YamlWithTags;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let secrets = tmp.path().join("secrets");
+    std::fs::create_dir(&secrets).unwrap();
+
+    let config = tmp.path().join("config.yaml");
+    let cycle = tmp.path().join("cycle.yaml");
+
+    // Paths are relative to the file that contains the tag
+    std::fs::write(
+        &config,
+        indoc::indoc!(
+            r#"
+            database:
+                kind: Postgres
+                schema_name: my_schema
+                host: !env SETTY_TEST_YAML_TAGS_HOST
+            encryption: !include secrets/encryption.yaml
+            "#
+        ),
+    )
+    .unwrap();
+    std::fs::write(
+        secrets.join("encryption.yaml"),
+        "algo: Rsa\nkey: !file key\n",
+    )
+    .unwrap();
+    std::fs::write(secrets.join("key"), "secret\n").unwrap();
+    std::fs::write(&cycle, "a: !include cycle.yaml\n").unwrap();
+
+    unsafe {
+        std::env::set_var("SETTY_TEST_YAML_TAGS_HOST", "my_host");
+    }
+
+    let cfg: MyConfig = setty::Config::new()
+        .with_source(setty::source::File::with_format(
+            &config,
+            YamlWithTags::new(),
+        ))
+        .extract()
+        .unwrap();
+
+    assert_eq!(
+        cfg,
+        MyConfig {
+            database: DatabaseConfig::Postgres(PostgresDatabaseConfig {
+                schema_name: "my_schema".into(),
+                host: "my_host".into()
+            }),
+            encryption: Some(EncryptionConfig {
+                key: "secret".into(),
+                algo: EncryptionAlgo::Rsa
+            }),
+        }
+    );
+
+    // Errors name the tag and the path
+    let format = YamlWithTags::new();
+
+    let err = format
+        .deserialize::<setty::Value>("a:\n  - !env SETTY_TEST_YAML_TAGS_UNSET")
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Failed to resolve tag `!env` at `a[0]`: Environment variable \
+         `SETTY_TEST_YAML_TAGS_UNSET` is not set or is not unicode"
+    );
+
+    let err = format
+        .deserialize::<setty::Value>("a: !vault x")
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Unknown tag `!vault` at `a`");
+
+    let err = format
+        .deserialize::<setty::Value>(&format!("b: !include {}", cycle.display()))
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .ends_with("Includes are nested deeper than 16 levels"),
+        "{err}"
+    );
+
+    // Resolved values are never written to documents with tags
+    use setty::format::DynFormat;
+
+    let new = serde_json::json!({"host": "h", "port": 2});
+    let err = DynFormat::update(&format, "host: !env HOST\nport: 1\n", &new).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Document with tags can't be updated without writing the resolved values"
+    );
+    assert_eq!(
+        DynFormat::update(&format, "host: h # host\nport: 1\n", &new).unwrap(),
+        "host: h # host\nport: 2\n"
+    );
+
+    unsafe {
+        std::env::remove_var("SETTY_TEST_YAML_TAGS_HOST");
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "fmt-yaml")]
+#[test]
+fn test_format_yaml_custom_tags() {
+    use setty::format::{FormatRegistry, TagContext, YamlTag, YamlWithTags};
+
+    // Handlers can hold state
+    struct Lookup(std::collections::BTreeMap<&'static str, &'static str>);
+
+    impl YamlTag for Lookup {
+        fn name(&self) -> &str {
+            "lookup"
+        }
+
+        fn resolve(
+            &self,
+            value: serde_yaml::Value,
+            ctx: &mut TagContext<'_>,
+        ) -> Result<serde_yaml::Value, Box<dyn std::error::Error + Send + Sync>> {
+            let key = value.as_str().ok_or("Expected a string")?;
+            let found = self.0.get(key).ok_or("Not found")?;
+            Ok(format!("{}={found}", ctx.path()).into())
+        }
+    }
+
+    let format = YamlWithTags::empty().with_tag(Lookup([("foo", "bar")].into()));
+
+    let value: setty::Value = format.deserialize("a:\n  b: !lookup foo\n").unwrap();
+    assert_eq!(value, serde_json::json!({"a": {"b": "a.b=bar"}}));
+
+    // Default tags are not available unless registered
+    let err = format.deserialize::<setty::Value>("a: !env X").unwrap_err();
+    assert_eq!(err.to_string(), "Unknown tag `!env` at `a`");
+
+    // Has its own name, but takes over YAML extensions when registered after `Yaml`
+    let registry = FormatRegistry::new()
+        .with_format::<setty::format::Yaml>()
+        .with_dyn_format(format);
+
+    assert_eq!(registry.by_name("yaml").unwrap().name(), "yaml");
+    assert_eq!(registry.by_name("yaml-tags").unwrap().name(), "yaml-tags");
+    assert_eq!(registry.for_path("config.yml").unwrap().name(), "yaml-tags");
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "fmt-json")]
+#[cfg(feature = "fmt-yaml")]
+#[cfg(feature = "fmt-toml")]
+#[test]
+fn test_format_registry() {
+    use setty::format::{DynFormat, FormatAdapter, FormatRegistry};
+
+    let registry = FormatRegistry::with_defaults();
+
+    assert_eq!(registry.by_name("YAML").unwrap().name(), "yaml");
+    assert_eq!(registry.by_extension("yml").unwrap().name(), "yaml");
+    assert_eq!(registry.for_path("a/config.toml").unwrap().name(), "toml");
+    assert!(registry.by_name("xml").is_none());
+    assert!(registry.for_path("config").is_none());
+
+    // Later formats take precedence
+    let registry = registry.with_dyn_format(setty::format::YamlWithTags::new());
+    assert!(
+        registry
+            .by_extension("yaml")
+            .unwrap()
+            .deserialize("a: !env SETTY_TEST_REGISTRY_UNSET")
+            .is_err()
+    );
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.yaml");
+    std::fs::write(
+        &path,
+        indoc::indoc!(
+            r#"
+            database:
+                kind: Postgres
+                schema_name: my_schema
+            "#
+        ),
+    )
+    .unwrap();
+
+    let format: Box<dyn DynFormat> = Box::new(FormatAdapter::<setty::format::Json>::new());
+
+    let cfg: MyConfig = setty::Config::new()
+        .with_source(setty::source::File::with_format(
+            &path,
+            registry.for_path(&path).unwrap(),
+        ))
+        .with_source(setty::source::RawData::with_format(
+            r#"{"database": {"kind": "Postgres", "host": "my_host"}}"#,
+            format,
+        ))
+        .extract()
+        .unwrap();
+
+    assert_eq!(
+        cfg,
+        MyConfig {
+            database: DatabaseConfig::Postgres(PostgresDatabaseConfig {
+                schema_name: "my_schema".into(),
+                host: "my_host".into()
+            }),
+            encryption: None,
+        }
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
@@ -0,0 +1,213 @@
+#![cfg(feature = "derive-jsonschema")]
+#![cfg(feature = "fmt-yaml")]
+#![cfg(feature = "fmt-toml")]
+
+use setty::errors::{ReadError, WriteError};
+use setty::format::{Toml, Yaml};
+use setty::source::File;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(setty::Config)]
+struct Cfg {
+    #[config(default)]
+    server: Server,
+    database: Option<Database>,
+    #[config(default)]
+    tags: Vec<String>,
+}
+
+#[derive(setty::Config, setty::Default)]
+struct Server {
+    #[config(default = "localhost")]
+    host: String,
+    #[config(default = 8080)]
+    port: u16,
+}
+
+#[derive(setty::Config)]
+struct Database {
+    url: String,
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_hostile_files() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let yamls = [
+        "",
+        "~",
+        "- 1\n- 2\n",
+        "just a string",
+        "server: [1, 2]\n",
+        "server:\n  port: -1\n",
+        "server: ~\ndatabase: ~\n",
+        "database: {url: [{}]}\n",
+        "tags: {a: b}\n",
+        "? [complex, key]\n: value\n",
+        "server: &a\n  host: *a\n",
+        "\"\\xff\": \u{1F600}\n",
+        "\t- \n:\n",
+    ];
+
+    for (i, text) in yamls.iter().enumerate() {
+        let path = tmp.path().join(format!("config-{i}.yaml"));
+        std::fs::write(&path, text).unwrap();
+
+        let config = setty::Config::<Cfg>::new()
+            .with_source(serde_json::json!({"server": {"host": "example.com"}}))
+            .with_source(File::<Yaml>::new(&path));
+
+        // Results don't matter as long as nothing panics
+        let _ = config.extract();
+        let _ = config.data(true);
+        let _ = config.get_value("server.port", true);
+        let _ = config.with_all_errors(true).extract();
+    }
+
+    let tomls = ["server = 1", "[server]\nport = \"x\"", "[[server]]", "= ="];
+
+    for (i, text) in tomls.iter().enumerate() {
+        let path = tmp.path().join(format!("config-{i}.toml"));
+        std::fs::write(&path, text).unwrap();
+
+        let config = setty::Config::<Cfg>::new().with_source(File::<Toml>::new(&path));
+
+        let _ = config.extract();
+        let _ = config.data(true);
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_hostile_data_with_defaults() {
+    // Defaults are not merged into values of a wrong type, deserialization reports them
+    let config = setty::Config::<Cfg>::new()
+        .with_source(serde_json::json!({"server": "localhost", "database": null}));
+
+    pretty_assertions::assert_eq!(
+        config.data(true).unwrap(),
+        serde_json::json!({"server": "localhost", "database": null, "tags": []})
+    );
+
+    let err = config.extract().unwrap_err();
+    assert!(
+        matches!(&err, ReadError::InvalidType { path, .. } if path == "server"),
+        "{err:?}"
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_hostile_set_value() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.toml");
+
+    let config = setty::Config::<Cfg>::new();
+
+    // TOML has no `null`
+    let err = config
+        .set_value::<Toml>("database", serde_json::Value::Null, &path)
+        .unwrap_err();
+    assert!(matches!(err, WriteError::Serialize(_)), "{err:?}");
+    assert!(!path.exists());
+
+    // Value of a wrong type is rejected before writing
+    let err = config
+        .set_value::<Toml>("server.port", "eighty", &path)
+        .unwrap_err();
+    assert!(matches!(err, WriteError::Read(_)), "{err:?}");
+    assert!(!path.exists());
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_hostile_unset_value() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.yaml");
+
+    let config = setty::Config::<Cfg>::new();
+
+    // Not UTF-8
+    std::fs::write(&path, b"server:\n  host: \xff\xfe\n").unwrap();
+    let err = config
+        .unset_value::<Yaml>("server.host", &path)
+        .unwrap_err();
+    assert!(
+        matches!(&err, WriteError::Io(e) if e.kind() == std::io::ErrorKind::InvalidData),
+        "{err:?}"
+    );
+
+    // Broken syntax
+    std::fs::write(&path, "server: [\n").unwrap();
+    let err = config
+        .unset_value::<Yaml>("server.host", &path)
+        .unwrap_err();
+    assert!(
+        matches!(&err, WriteError::Read(ReadError::Syntax { .. })),
+        "{err:?}"
+    );
+
+    // Root is not a map, so the config is invalid and file is left as is
+    std::fs::write(&path, "- a\n").unwrap();
+    let err = config
+        .unset_value::<Yaml>("server.host", &path)
+        .unwrap_err();
+    assert!(matches!(&err, WriteError::Read(_)), "{err:?}");
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "- a\n");
+
+    // Missing file
+    let err = config
+        .unset_value::<Yaml>("server.host", &tmp.path().join("missing.yaml"))
+        .unwrap_err();
+    assert!(matches!(&err, WriteError::Io(_)), "{err:?}");
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "gen-markdown")]
+#[test]
+fn test_hostile_markdown_exotic_schemas() {
+    use setty::__internal::schemars::Schema;
+
+    let schemas = [
+        // Untitled
+        serde_json::json!({"type": "object"}),
+        // Not an object at all
+        serde_json::json!(true),
+        // Externally tagged enum
+        serde_json::json!({
+            "title": "Cfg",
+            "oneOf": [
+                {"type": "object", "properties": {"A": {"type": "string"}}, "required": ["A"]},
+                {"const": "B"},
+            ],
+        }),
+        // Untagged enum and dangling references
+        serde_json::json!({
+            "title": "Cfg",
+            "type": "object",
+            "properties": {
+                "untagged": {"oneOf": [{"type": "string"}, {"type": "integer"}]},
+                "dangling": {"$ref": "#/$defs/Missing"},
+                "map": {"type": "object", "additionalProperties": {"type": "integer"}},
+                "union": {"type": ["string", "integer", "null"]},
+                "nothing": {},
+                "mixed_enum": {"type": "string", "enum": ["a", 1, null]},
+            },
+            "required": ["untagged", 5],
+        }),
+    ];
+
+    for schema in schemas {
+        let schema = Schema::try_from(schema).unwrap();
+        let _ = setty::markdown::schema_to_markdown(&schema);
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
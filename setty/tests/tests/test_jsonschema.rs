@@ -45,6 +45,47 @@ fn test_json_schema_combine_attrs() {
 
 /////////////////////////////////////////////////////////////////////////////////////////
 
+#[test]
+fn test_json_schema_reload_attrs() {
+    #[derive(setty::Config)]
+    struct Cfg {
+        #[config(default = 8080, reload = "restart")]
+        port: u16,
+
+        #[config(default, reload = "hot")]
+        log_level: String,
+    }
+
+    let schema = setty::Config::<Cfg>::new().json_schema();
+
+    pretty_assertions::assert_eq!(
+        *schema.as_value(),
+        serde_json::json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "additionalProperties": false,
+            "properties": {
+                "port": {
+                    "default": 8080,
+                    "format": "uint16",
+                    "maximum": 65535,
+                    "minimum": 0,
+                    "reload": "restart",
+                    "type": "integer",
+                },
+                "log_level": {
+                    "default": "",
+                    "reload": "hot",
+                    "type": "string",
+                },
+            },
+            "title": "Cfg",
+            "type": "object",
+        }),
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
 #[test]
 fn test_json_schema_deprecaion() {
     #[derive(setty::Config)]
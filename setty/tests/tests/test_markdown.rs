@@ -41,8 +41,97 @@ fn test_markdown() {
         a: u32,
     }
 
+    let markdown = setty::Config::<Cfg>::new().markdown();
+
+    // Fields are listed in declaration order
+    #[cfg(feature = "preserve-order")]
+    pretty_assertions::assert_eq!(
+        markdown,
+        indoc::indoc!(
+            r###"
+            ## `Cfg`
+
+            <table>
+            <thead><tr><th>Field</th><th>Type</th><th>Default</th><th>Description</th></tr></thead>
+            <tbody>
+            <tr>
+            <td><code>required</code></td>
+            <td><code>string</code></td>
+            <td></td>
+            <td>Required</td>
+            </tr>
+            <tr>
+            <td><code>with_default</code></td>
+            <td><code>string</code></td>
+            <td><code class="language-json">&quot;foo&quot;</code></td>
+            <td>With default</td>
+            </tr>
+            <tr>
+            <td><code>option</code></td>
+            <td><code>string</code></td>
+            <td><code class="language-json">null</code></td>
+            <td>Option</td>
+            </tr>
+            <tr>
+            <td><code>nested</code></td>
+            <td><a href="#foo"><code>Foo</code></a></td>
+            <td><pre><code class="language-json">{
+              &quot;a&quot;: 0
+            }</code></pre></td>
+            <td>Nested</td>
+            </tr>
+            <tr>
+            <td><code>multiline_desc</code></td>
+            <td><code>integer</code></td>
+            <td></td>
+            <td>
+
+            Multi-line
+
+            Description.
+
+            Has many lines.
+
+            </td>
+            </tr>
+            <tr>
+            <td><code>code_desc</code></td>
+            <td><code>integer</code></td>
+            <td></td>
+            <td>
+
+            Lorem ipsum
+
+            ```sh
+            cat "foo"
+            ```
+
+            </td>
+            </tr>
+            </tbody>
+            </table>
+
+            ## `Foo`
+
+            <table>
+            <thead><tr><th>Field</th><th>Type</th><th>Default</th><th>Description</th></tr></thead>
+            <tbody>
+            <tr>
+            <td><code>a</code></td>
+            <td><code>integer</code></td>
+            <td><code class="language-json">0</code></td>
+            <td></td>
+            </tr>
+            </tbody>
+            </table>
+            "###
+        )
+    );
+
+    // Fields are listed alphabetically
+    #[cfg(not(feature = "preserve-order"))]
     pretty_assertions::assert_eq!(
-        setty::Config::<Cfg>::new().markdown(),
+        markdown,
         indoc::indoc!(
             r###"
             ## `Cfg`
@@ -126,3 +215,62 @@ fn test_markdown() {
 }
 
 /////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "gen-markdown")]
+#[test]
+fn test_markdown_env_vars() {
+    #[derive(setty::Config)]
+    struct Cfg {
+        /// Nested
+        #[config(default)]
+        nested: Foo,
+
+        /// Option
+        option: Option<String>,
+    }
+
+    #[derive(setty::Config, setty::Default)]
+    struct Foo {
+        /// Timeout in seconds
+        #[config(default = 30)]
+        timeout: u32,
+    }
+
+    let cfg = setty::Config::<Cfg>::new();
+
+    pretty_assertions::assert_eq!(
+        setty::markdown::env_vars_to_markdown(&cfg.env_vars("APP_", "__")),
+        indoc::indoc!(
+            r###"
+            ## Environment Variables
+
+            <table>
+            <thead><tr><th>Variable</th><th>Type</th><th>Default</th><th>Description</th></tr></thead>
+            <tbody>
+            <tr>
+            <td><code>APP_nested__timeout</code></td>
+            <td><code>integer</code></td>
+            <td><code class="language-json">30</code></td>
+            <td>Timeout in seconds</td>
+            </tr>
+            <tr>
+            <td><code>APP_option</code></td>
+            <td><code>string</code></td>
+            <td><code class="language-json">null</code></td>
+            <td>Option</td>
+            </tr>
+            </tbody>
+            </table>
+            "###
+        )
+    );
+
+    // Names are escaped same as other cells
+    let md = setty::markdown::env_vars_to_markdown(&cfg.env_vars("<APP>_", "__"));
+    assert!(
+        md.contains("<td><code>&lt;APP&gt;_option</code></td>"),
+        "{md}"
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
@@ -0,0 +1,390 @@
+use setty::errors::PathError;
+use setty::path::{KeyPath, Segment};
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_path_parse() {
+    let key = |k: &str| Segment::Key(k.to_string());
+
+    let cases = [
+        ("", vec![]),
+        ("a", vec![key("a")]),
+        ("a.b-c.d_e", vec![key("a"), key("b-c"), key("d_e")]),
+        ("hosts[1]", vec![key("hosts"), Segment::Index(1)]),
+        (
+            "[0][1].a",
+            vec![Segment::Index(0), Segment::Index(1), key("a")],
+        ),
+        (
+            r#"labels."app.kubernetes.io/name""#,
+            vec![key("labels"), key("app.kubernetes.io/name")],
+        ),
+        (r#""a\"b\\c".d"#, vec![key(r#"a"b\c"#), key("d")]),
+        (r#""*""#, vec![key("*")]),
+        (r#""""#, vec![key("")]),
+        (
+            "clusters.*.timeout",
+            vec![key("clusters"), Segment::Wildcard, key("timeout")],
+        ),
+        ("hosts[*]", vec![key("hosts"), Segment::Wildcard]),
+        ("a/b.~c", vec![key("a/b"), key("~c")]),
+    ];
+
+    for (input, segments) in cases {
+        let path = KeyPath::parse(input).unwrap();
+        pretty_assertions::assert_eq!(path.segments(), segments, "{input}");
+
+        // Display renders the path that parses back into the same segments
+        let reparsed: KeyPath = path.to_string().parse().unwrap();
+        pretty_assertions::assert_eq!(reparsed, path, "{input}");
+    }
+
+    pretty_assertions::assert_eq!(KeyPath::parse("hosts[*]").unwrap().to_string(), "hosts.*");
+    pretty_assertions::assert_eq!(
+        KeyPath::parse(r#""a"."b c"[0]"#).unwrap().to_string(),
+        r#"a."b c"[0]"#
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_path_parse_errors() {
+    let cases = [
+        ("a.", 2, "expected a key"),
+        (".a", 0, "expected a key"),
+        ("a..b", 2, "expected a key"),
+        ("a[1", 1, "unclosed `[`"),
+        ("a[x]", 2, "expected an index or `*`"),
+        ("a[-1]", 2, "expected an index or `*`"),
+        (r#"a."b"#, 2, "unterminated quotes"),
+        (r#""a\n""#, 2, "invalid escape"),
+        (r#""a"b"#, 3, "expected `.` or `[`"),
+        ("a]", 1, "expected `.` or `[`"),
+        ("a b", 1, "expected `.` or `[`"),
+    ];
+
+    for (input, expected_position, expected_reason) in cases {
+        match KeyPath::parse(input) {
+            Err(PathError::Syntax {
+                path,
+                position,
+                reason,
+            }) => {
+                assert_eq!(path, input);
+                assert_eq!(
+                    (position, reason),
+                    (expected_position, expected_reason),
+                    "{input}"
+                );
+            }
+            res => panic!("Unexpected result for {input}: {res:?}"),
+        }
+    }
+
+    pretty_assertions::assert_eq!(
+        KeyPath::parse("a..b").unwrap_err().to_string(),
+        "Invalid path `a..b` at position 2: expected a key"
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_path_assignment() {
+    let (path, value) = KeyPath::parse_assignment("a.b=c=d").unwrap();
+    pretty_assertions::assert_eq!((path.to_string().as_str(), value), ("a.b", "c=d"));
+
+    let (path, value) = KeyPath::parse_assignment(r#""x=y".z="#).unwrap();
+    pretty_assertions::assert_eq!(path.segments()[0], Segment::Key("x=y".into()));
+    pretty_assertions::assert_eq!(value, "");
+
+    assert!(matches!(
+        KeyPath::parse_assignment("a.b"),
+        Err(PathError::Syntax { position: 3, .. })
+    ));
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_path_select_set_remove() {
+    let path = |p: &str| KeyPath::parse(p).unwrap();
+
+    let mut value = serde_json::json!({
+        "clusters": {
+            "a": {"timeout": 1},
+            "b": {"timeout": 2, "name": "b"},
+            "c": {},
+        },
+        "hosts": ["x", "y", "z"],
+        "labels": {"app.kubernetes.io/name": "web"},
+    });
+
+    // Select
+    pretty_assertions::assert_eq!(
+        path("hosts[1]").select(&value),
+        Some(serde_json::json!("y"))
+    );
+    pretty_assertions::assert_eq!(path("hosts[3]").select(&value), None);
+    pretty_assertions::assert_eq!(path("hosts.a").select(&value), None);
+    pretty_assertions::assert_eq!(
+        path(r#"labels."app.kubernetes.io/name""#).select(&value),
+        Some(serde_json::json!("web"))
+    );
+    pretty_assertions::assert_eq!(
+        path("clusters.*.timeout").select(&value),
+        Some(serde_json::json!({"a": 1, "b": 2}))
+    );
+    pretty_assertions::assert_eq!(
+        path("hosts[*]").select(&value),
+        Some(serde_json::json!(["x", "y", "z"]))
+    );
+    pretty_assertions::assert_eq!(path("").select(&value), Some(value.clone()));
+
+    // Set
+    path("hosts[1]")
+        .set(&mut value, serde_json::json!("Y"))
+        .unwrap();
+    path("clusters.*.timeout")
+        .set(&mut value, serde_json::json!(30))
+        .unwrap();
+    path("new.nested.key")
+        .set(&mut value, serde_json::json!(true))
+        .unwrap();
+
+    pretty_assertions::assert_eq!(
+        value,
+        serde_json::json!({
+            "clusters": {
+                "a": {"timeout": 30},
+                "b": {"timeout": 30, "name": "b"},
+                "c": {"timeout": 30},
+            },
+            "hosts": ["x", "Y", "z"],
+            "labels": {"app.kubernetes.io/name": "web"},
+            "new": {"nested": {"key": true}},
+        })
+    );
+
+    pretty_assertions::assert_eq!(
+        path("hosts[3]")
+            .set(&mut value, serde_json::json!("w"))
+            .unwrap_err()
+            .to_string(),
+        "Index 3 is out of bounds at `hosts` of length 3"
+    );
+    pretty_assertions::assert_eq!(
+        path("hosts.a")
+            .set(&mut value, serde_json::json!("w"))
+            .unwrap_err()
+            .to_string(),
+        "Expected object at `hosts`, found array"
+    );
+    pretty_assertions::assert_eq!(
+        path("labels.*.x")
+            .set(&mut value, serde_json::json!(1))
+            .unwrap_err()
+            .to_string(),
+        r#"Expected object at `labels."app.kubernetes.io/name"`, found string"#
+    );
+    path("new.*").set(&mut value, serde_json::json!(1)).unwrap();
+    pretty_assertions::assert_eq!(
+        path("empty.*")
+            .set(&mut serde_json::json!({"empty": []}), serde_json::json!(1))
+            .unwrap_err()
+            .to_string(),
+        "Path `empty.*` does not match any values"
+    );
+
+    // Remove
+    pretty_assertions::assert_eq!(
+        path("hosts[0]").remove(&mut value),
+        Some(serde_json::json!("x"))
+    );
+    pretty_assertions::assert_eq!(
+        path("clusters.*.name").remove(&mut value),
+        Some(serde_json::json!({"b": "b"}))
+    );
+    pretty_assertions::assert_eq!(path("clusters.*.name").remove(&mut value), None);
+    pretty_assertions::assert_eq!(path("missing.key").remove(&mut value), None);
+    pretty_assertions::assert_eq!(
+        path("labels.*").remove(&mut value),
+        Some(serde_json::json!({"app.kubernetes.io/name": "web"}))
+    );
+
+    pretty_assertions::assert_eq!(
+        value,
+        serde_json::json!({
+            "clusters": {
+                "a": {"timeout": 30},
+                "b": {"timeout": 30},
+                "c": {"timeout": 30},
+            },
+            "hosts": ["Y", "z"],
+            "labels": {},
+            "new": {"nested": 1},
+        })
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "derive-deserialize")]
+#[cfg(feature = "derive-jsonschema")]
+#[cfg(feature = "fmt-yaml")]
+#[test]
+fn test_path_config_ops() {
+    use std::collections::BTreeMap;
+
+    #[derive(setty::Config)]
+    struct Cfg {
+        #[config(default)]
+        hosts: Vec<Host>,
+        #[config(default)]
+        clusters: BTreeMap<String, Cluster>,
+        #[config(default)]
+        labels: BTreeMap<String, String>,
+    }
+
+    #[derive(setty::Config)]
+    struct Host {
+        name: String,
+        #[config(default = 80)]
+        port: u16,
+    }
+
+    #[derive(setty::Config)]
+    struct Cluster {
+        timeout: u32,
+    }
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.yaml");
+
+    std::fs::write(
+        &path,
+        indoc::indoc!(
+            r#"
+            hosts:
+              - name: a
+              - name: b
+            clusters:
+              east:
+                timeout: 1
+              west:
+                timeout: 2
+            labels:
+              app.kubernetes.io/name: web
+            "#
+        ),
+    )
+    .unwrap();
+
+    let fig = setty::Config::<Cfg>::new()
+        .with_source(setty::source::File::<setty::format::Yaml>::new(&path));
+
+    // Get
+    pretty_assertions::assert_eq!(
+        fig.get_value("hosts[1].name", false).unwrap(),
+        Some("b".into())
+    );
+    pretty_assertions::assert_eq!(fig.get::<u16>("hosts[0].port", false).unwrap(), None);
+    pretty_assertions::assert_eq!(
+        fig.get::<String>(r#"labels."app.kubernetes.io/name""#, false)
+            .unwrap(),
+        Some("web".into())
+    );
+    pretty_assertions::assert_eq!(
+        fig.get::<BTreeMap<String, u32>>("clusters.*.timeout", false)
+            .unwrap(),
+        Some([("east".into(), 1), ("west".into(), 2)].into())
+    );
+
+    // Errors point at the concrete entry
+    let err = fig
+        .get::<BTreeMap<String, bool>>("clusters.*.timeout", false)
+        .unwrap_err();
+    pretty_assertions::assert_eq!(err.path(), Some("clusters.east.timeout"));
+    pretty_assertions::assert_eq!(err.location().unwrap().line, Some(6));
+
+    assert!(matches!(
+        fig.get_value("hosts[", false),
+        Err(setty::errors::ReadError::Path(PathError::Syntax { .. }))
+    ));
+
+    // Set
+    fig.set_value::<setty::format::Yaml>("hosts[1].port", 8080, &path)
+        .unwrap();
+    fig.set_value::<setty::format::Yaml>("clusters.*.timeout", 30, &path)
+        .unwrap();
+    fig.set_value::<setty::format::Yaml>(r#"labels."app.kubernetes.io/name""#, "api", &path)
+        .unwrap();
+
+    let err = fig
+        .set_value::<setty::format::Yaml>("hosts[5].port", 1, &path)
+        .unwrap_err();
+    pretty_assertions::assert_eq!(
+        err.to_string(),
+        "Index 5 is out of bounds at `hosts` of length 2"
+    );
+    // Changed entries are validated
+    assert!(
+        fig.set_value::<setty::format::Yaml>("hosts[0].port", "eighty", &path)
+            .is_err()
+    );
+
+    pretty_assertions::assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        indoc::indoc!(
+            r#"
+            hosts:
+              - name: a
+              - name: b
+                port: 8080
+            clusters:
+              east:
+                timeout: 30
+              west:
+                timeout: 30
+            labels:
+              app.kubernetes.io/name: api
+            "#
+        )
+    );
+
+    // Unset
+    pretty_assertions::assert_eq!(
+        fig.unset_value::<setty::format::Yaml>("hosts[0]", &path)
+            .unwrap(),
+        Some(serde_json::json!({"name": "a"}))
+    );
+    pretty_assertions::assert_eq!(
+        fig.unset_value::<setty::format::Yaml>(r#"labels."app.kubernetes.io/name""#, &path)
+            .unwrap(),
+        Some("api".into())
+    );
+
+    let cfg = fig.extract().unwrap();
+    pretty_assertions::assert_eq!(cfg.hosts.len(), 1);
+    pretty_assertions::assert_eq!(cfg.hosts[0].port, 8080);
+    assert!(cfg.labels.is_empty());
+
+    // Complete
+    let mut completions = fig.complete_path("");
+    completions.sort();
+    pretty_assertions::assert_eq!(
+        completions,
+        [
+            "clusters",
+            "clusters.*.timeout",
+            "hosts",
+            "hosts.*.name",
+            "hosts.*.port",
+            "labels",
+        ]
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
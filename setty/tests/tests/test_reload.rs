@@ -0,0 +1,177 @@
+#![cfg(feature = "derive-jsonschema")]
+
+use setty::reload::ReloadPlan;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(setty::Config)]
+struct Cfg {
+    #[config(default)]
+    server: Server,
+
+    /// Whole section requires restart
+    #[config(default, reload = "restart")]
+    pool: Pool,
+
+    #[config(default)]
+    log_level: String,
+
+    database: Option<Database>,
+
+    #[config(default)]
+    labels: std::collections::BTreeMap<String, String>,
+}
+
+#[derive(setty::Config, setty::Default)]
+struct Server {
+    #[config(default)]
+    host: String,
+    #[config(default = 8080, reload = "restart")]
+    port: u16,
+}
+
+#[derive(setty::Config, setty::Default)]
+struct Pool {
+    #[config(default = 4)]
+    threads: u32,
+    /// Overrides the policy of the parent
+    #[config(default = 10, reload = "hot")]
+    queue_size: u32,
+}
+
+#[derive(setty::Config)]
+enum Database {
+    Postgres(Postgres),
+    Sqlite(Sqlite),
+}
+
+#[derive(setty::Config)]
+struct Postgres {
+    #[config(reload = "restart")]
+    url: String,
+    #[config(default = 5)]
+    timeout: u32,
+}
+
+#[derive(setty::Config)]
+struct Sqlite {
+    path: String,
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+fn plan(old: serde_json::Value, new: serde_json::Value) -> ReloadPlan {
+    let data = |v| {
+        setty::Config::<Cfg>::new()
+            .with_source(v)
+            .data(true)
+            .unwrap()
+    };
+    let schema = setty::Config::<Cfg>::new().json_schema();
+    ReloadPlan::new(&schema, &data(old), &data(new))
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_reload_plan() {
+    // No changes
+    let p = plan(
+        serde_json::json!({}),
+        serde_json::json!({"server": {"port": 8080}}),
+    );
+    assert!(p.is_empty());
+    assert!(!p.requires_restart());
+
+    // Field policies
+    let p = plan(
+        serde_json::json!({}),
+        serde_json::json!({
+            "server": {"host": "example.com", "port": 80},
+            "log_level": "debug",
+        }),
+    );
+    pretty_assertions::assert_eq!(
+        p,
+        ReloadPlan {
+            hot: vec!["log_level".into(), "server.host".into()],
+            restart: vec!["server.port".into()],
+        }
+    );
+    assert!(p.requires_restart());
+
+    // Inherited and overridden policies
+    let p = plan(
+        serde_json::json!({}),
+        serde_json::json!({"pool": {"threads": 8, "queue_size": 100}}),
+    );
+    pretty_assertions::assert_eq!(
+        p,
+        ReloadPlan {
+            hot: vec!["pool.queue_size".into()],
+            restart: vec!["pool.threads".into()],
+        }
+    );
+
+    // Enum variants and maps
+    let p = plan(
+        serde_json::json!({
+            "database": {"kind": "Postgres", "url": "pg://a"},
+            "labels": {"a": "1"},
+        }),
+        serde_json::json!({
+            "database": {"kind": "Postgres", "url": "pg://b", "timeout": 10},
+            "labels": {"b": "2"},
+        }),
+    );
+    pretty_assertions::assert_eq!(
+        p,
+        ReloadPlan {
+            hot: vec![
+                "database.timeout".into(),
+                "labels.a".into(),
+                "labels.b".into()
+            ],
+            restart: vec!["database.url".into()],
+        }
+    );
+
+    // Value added or removed as a whole
+    let p = plan(
+        serde_json::json!({}),
+        serde_json::json!({"database": {"kind": "Sqlite", "path": "db.sqlite"}}),
+    );
+    pretty_assertions::assert_eq!(p.hot, ["database"]);
+
+    // Special keys are quoted
+    let p = plan(
+        serde_json::json!({}),
+        serde_json::json!({"labels": {"app.kubernetes.io/name": "x", "a b": "y"}}),
+    );
+    pretty_assertions::assert_eq!(
+        p.hot,
+        [r#"labels."a b""#, r#"labels."app.kubernetes.io/name""#]
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "derive-serialize")]
+#[test]
+fn test_reload_plan_between_configs() {
+    let extract = |v| {
+        setty::Config::<Cfg>::new()
+            .with_source(v)
+            .extract()
+            .unwrap()
+    };
+
+    let old = extract(serde_json::json!({}));
+    let new = extract(serde_json::json!({"server": {"port": 80}}));
+
+    let p = ReloadPlan::between(&old, &new).unwrap();
+    pretty_assertions::assert_eq!(p.restart, ["server.port"]);
+    assert!(p.hot.is_empty());
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
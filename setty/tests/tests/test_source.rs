@@ -33,6 +33,180 @@ fn test_env_var_override_nested() {
 
 /////////////////////////////////////////////////////////////////////////////////////////
 
+#[cfg(feature = "fmt-yaml")]
+#[test]
+fn test_yaml_multi_document_layers() {
+    use setty::source::Source;
+
+    #[derive(setty::Config)]
+    struct Cfg {
+        #[config(default)]
+        name: String,
+        #[config(default, combine(merge))]
+        tags: Vec<String>,
+    }
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.yaml");
+    std::fs::write(
+        &path,
+        indoc::indoc!(
+            "
+            name: a
+            tags: [x]
+            ---
+            ---
+            name: b
+            tags: [y]
+            "
+        ),
+    )
+    .unwrap();
+
+    let source = setty::source::File::<setty::format::Yaml>::new(&path);
+
+    let names: Vec<_> = source
+        .load_layers()
+        .unwrap()
+        .into_iter()
+        .map(|layer| layer.name.into_owned())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            format!("{}#1", path.display()),
+            format!("{}#3", path.display()),
+        ]
+    );
+
+    // Documents are combined in order
+    let cfg: Cfg = setty::Config::new().with_source(source).extract().unwrap();
+    assert_eq!(cfg.name, "b");
+    assert_eq!(cfg.tags, ["x", "y"]);
+
+    // Single document keeps the source name
+    let source = setty::source::RawData::<setty::format::Yaml>::new("name: a");
+    let layers = source.load_layers().unwrap();
+    assert_eq!(layers.len(), 1);
+    assert_eq!(layers[0].name, "<raw yaml data>");
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "fmt-yaml")]
+#[test]
+fn test_overrides() {
+    use setty::source::{Overrides, Source as _};
+
+    let source = Overrides::<setty::format::Yaml>::new([
+        "database.kind=Postgres",
+        "database.schema_name=foo",
+        "database.host=a",
+        "database.host=b",
+    ]);
+
+    let cfg: MyConfig = setty::Config::new().with_source(source).extract().unwrap();
+
+    assert_eq!(
+        cfg.database,
+        DatabaseConfig::Postgres(PostgresDatabaseConfig {
+            schema_name: "foo".into(),
+            host: "b".into(),
+        })
+    );
+
+    // Quoted keys and values parsed by the format
+    let source = Overrides::<setty::format::Yaml>::new([
+        r#"labels."app.kubernetes.io/name"=web"#,
+        "port=5432",
+        "empty=",
+    ]);
+    pretty_assertions::assert_eq!(
+        source.load().unwrap(),
+        Some(serde_json::json!({
+            "empty": null,
+            "labels": {"app.kubernetes.io/name": "web"},
+            "port": 5432,
+        }))
+    );
+
+    // Entries of other sources can't be addressed
+    let err = Overrides::<setty::format::Yaml>::new(["hosts[0]=a"])
+        .load()
+        .unwrap_err();
+    pretty_assertions::assert_eq!(
+        err.to_string(),
+        "Path `hosts[0]` is not supported here: overrides can only set keys"
+    );
+
+    let err = Overrides::<setty::format::Yaml>::new(["a.b"])
+        .load()
+        .unwrap_err();
+    pretty_assertions::assert_eq!(
+        err.to_string(),
+        "Invalid path `a.b` at position 3: expected `=`"
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(
+    feature = "derive-jsonschema",
+    feature = "fmt-ini",
+    feature = "fmt-properties",
+    feature = "fmt-ron"
+))]
+#[test]
+fn test_env_and_overrides_convert_values() {
+    use setty::format::{Ini, Properties, Ron};
+    use setty::source::{Env, Overrides};
+
+    #[derive(setty::Config)]
+    struct Cfg {
+        #[config(default)]
+        port: u16,
+        #[config(default)]
+        enabled: bool,
+        database: Option<DatabaseConfig>,
+    }
+
+    let _a = set_env_var("SETTY_CONVERT__port", "8080");
+    let _b = set_env_var("SETTY_CONVERT__enabled", "true");
+
+    // Formats without types take values as strings converted by the schema
+    let cfg: Cfg = setty::Config::new()
+        .with_source(Env::<Properties>::new("SETTY_CONVERT__", "__"))
+        .extract()
+        .unwrap();
+    assert_eq!(cfg.port, 8080);
+    assert!(cfg.enabled);
+
+    let cfg: Cfg = setty::Config::new()
+        .with_source(Env::<Ini>::new("SETTY_CONVERT__", "__"))
+        .with_source(Overrides::<Properties>::new(["port=8081"]))
+        .extract()
+        .unwrap();
+    assert_eq!(cfg.port, 8081);
+    assert!(cfg.enabled);
+
+    // Names of enum variants are mapped onto tags
+    let cfg: Cfg = setty::Config::new()
+        .with_source(Overrides::<Ron>::new([
+            r#"database=Postgres(schema_name: "foo", host: "h")"#,
+        ]))
+        .extract()
+        .unwrap();
+    assert_eq!(
+        cfg.database,
+        Some(DatabaseConfig::Postgres(PostgresDatabaseConfig {
+            schema_name: "foo".into(),
+            host: "h".into(),
+        }))
+    );
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
 fn set_env_var(k: &'static str, v: &'static str) -> Unset {
     let unset = Unset(k);
     unsafe {
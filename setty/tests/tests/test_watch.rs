@@ -0,0 +1,223 @@
+#![cfg(feature = "watch")]
+#![cfg(feature = "derive-deserialize")]
+#![cfg(feature = "fmt-yaml")]
+
+use std::sync::{Arc, mpsc};
+use std::time::Duration;
+
+use setty::format::Yaml;
+use setty::source::File;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(setty::Config)]
+struct Cfg {
+    #[config(default = 8080)]
+    port: u16,
+}
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_watch_reload() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.yaml");
+    std::fs::write(&path, "port: 1\n").unwrap();
+
+    let live = setty::Config::watch({
+        let path = path.clone();
+        move || {
+            setty::Config::<Cfg>::new()
+                .with_source(File::<Yaml>::new(&path))
+                .with_watch_debounce(Duration::from_millis(50))
+        }
+    })
+    .unwrap();
+
+    assert_eq!(live.load().port, 1);
+    assert_eq!(live.paths(), vec![path.clone()]);
+
+    let (changes_tx, changes_rx) = mpsc::channel();
+    live.subscribe(move |old, new| changes_tx.send((old.port, new.port)).unwrap());
+
+    let (errors_tx, errors_rx) = mpsc::channel();
+    live.on_error(move |err| errors_tx.send(err.to_string()).unwrap());
+
+    // Change is picked up
+    std::fs::write(&path, "port: 2\n").unwrap();
+    assert_eq!(changes_rx.recv_timeout(TIMEOUT).unwrap(), (1, 2));
+    assert_eq!(live.load().port, 2);
+
+    // Invalid config is reported and the last good one is kept
+    std::fs::write(&path, "port: eighty\n").unwrap();
+    let err = errors_rx.recv_timeout(TIMEOUT).unwrap();
+    assert!(err.starts_with("Invalid type at `port`"), "{err}");
+    assert_eq!(live.load().port, 2);
+
+    // File replaced via rename like editors do
+    let tmp_path = tmp.path().join("config.yaml.tmp");
+    std::fs::write(&tmp_path, "port: 3\n").unwrap();
+    std::fs::rename(&tmp_path, &path).unwrap();
+    assert_eq!(changes_rx.recv_timeout(TIMEOUT).unwrap(), (2, 3));
+    assert_eq!(live.load().port, 3);
+
+    // Unrelated files are ignored
+    std::fs::write(tmp.path().join("other.yaml"), "port: 4\n").unwrap();
+    std::thread::sleep(Duration::from_millis(500));
+    assert!(changes_rx.try_iter().all(|(_, new)| new == 3));
+    assert_eq!(live.load().port, 3);
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_watch_manual_reload() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.yaml");
+
+    // Optional file that doesn't exist yet
+    let live = setty::Config::watch({
+        let path = path.clone();
+        move || setty::Config::<Cfg>::new().with_source(File::<Yaml>::new(&path).required(false))
+    })
+    .unwrap();
+
+    assert_eq!(live.load().port, 8080);
+
+    let old = live.load();
+    std::fs::write(&path, "port: eighty\n").unwrap();
+    assert!(live.reload().is_err());
+    assert!(Arc::ptr_eq(&old, &live.load()));
+
+    std::fs::write(&path, "port: 1\n").unwrap();
+    assert_eq!(live.reload().unwrap().port, 1);
+    assert_eq!(live.clone().load().port, 1);
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_watch_initial_error() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.yaml");
+    std::fs::write(&path, "port: eighty\n").unwrap();
+
+    let err = setty::Config::watch(move || {
+        setty::Config::<Cfg>::new().with_source(File::<Yaml>::new(&path))
+    })
+    .err()
+    .unwrap();
+
+    assert_eq!(err.path(), Some("port"));
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_watch_reentrant_subscriber() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.yaml");
+    std::fs::write(&path, "port: 1\n").unwrap();
+
+    let live = setty::Config::watch({
+        let path = path.clone();
+        move || setty::Config::<Cfg>::new().with_source(File::<Yaml>::new(&path))
+    })
+    .unwrap();
+
+    let (changes_tx, changes_rx) = mpsc::channel();
+
+    // Subscriber reloads and subscribes again without deadlocking, changes are
+    // still delivered in order
+    let handle = live.clone();
+    live.subscribe(move |old, new| {
+        changes_tx.send((old.port, new.port)).unwrap();
+        if new.port == 2 {
+            std::fs::write(handle.paths()[0].clone(), "port: 3\n").unwrap();
+            handle.reload().unwrap();
+            handle.subscribe(|_, _| {});
+        }
+    });
+
+    std::fs::write(&path, "port: 2\n").unwrap();
+    assert_eq!(live.reload().unwrap().port, 2);
+    assert_eq!(
+        changes_rx.try_iter().take(2).collect::<Vec<_>>(),
+        [(1, 2), (2, 3)]
+    );
+    assert_eq!(live.load().port, 3);
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_watch_included_files() {
+    use setty::format::YamlWithTags;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.yaml");
+    let port_path = tmp.path().join("port.yaml");
+    std::fs::write(&path, "port: !include port.yaml\n").unwrap();
+    std::fs::write(&port_path, "1\n").unwrap();
+
+    let live = setty::Config::watch({
+        let path = path.clone();
+        move || {
+            setty::Config::<Cfg>::new()
+                .with_source(File::with_format(&path, YamlWithTags::new()))
+                .with_watch_debounce(Duration::from_millis(50))
+        }
+    })
+    .unwrap();
+
+    assert_eq!(live.load().port, 1);
+    assert_eq!(live.paths(), vec![path.clone(), port_path.clone()]);
+
+    let (changes_tx, changes_rx) = mpsc::channel();
+    live.subscribe(move |old, new| changes_tx.send((old.port, new.port)).unwrap());
+
+    // Change of the included file is picked up
+    std::fs::write(&port_path, "2\n").unwrap();
+    assert_eq!(changes_rx.recv_timeout(TIMEOUT).unwrap(), (1, 2));
+
+    // Files included later are watched after reload
+    let sub_dir = tmp.path().join("sub");
+    std::fs::create_dir(&sub_dir).unwrap();
+    std::fs::write(sub_dir.join("port.yaml"), "3\n").unwrap();
+    std::fs::write(&path, "port: !include sub/port.yaml\n").unwrap();
+    assert_eq!(changes_rx.recv_timeout(TIMEOUT).unwrap(), (2, 3));
+    assert_eq!(live.paths(), vec![path.clone(), sub_dir.join("port.yaml")]);
+
+    std::fs::write(sub_dir.join("port.yaml"), "4\n").unwrap();
+    assert_eq!(changes_rx.recv_timeout(TIMEOUT).unwrap(), (3, 4));
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Sources don't need to be `Send + Sync` as they are created by the builder
+#[test]
+fn test_watch_local_source() {
+    use std::rc::Rc;
+
+    struct Local(Rc<u16>);
+
+    impl setty::source::Source for Local {
+        fn name(&self) -> std::borrow::Cow<'static, str> {
+            "local".into()
+        }
+
+        fn load(&self) -> Result<Option<setty::Value>, setty::errors::ReadError> {
+            Ok(Some(serde_json::json!({ "port": *self.0 })))
+        }
+    }
+
+    let live = setty::Config::watch(|| setty::Config::<Cfg>::new().with_source(Local(Rc::new(1))))
+        .unwrap();
+
+    assert_eq!(live.load().port, 1);
+    assert!(live.paths().is_empty());
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////